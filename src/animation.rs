@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_game_2::chess_plugin::{MoveRejected, PieceCaptured, PieceMoved, Square};
+
+use crate::{PieceAssets, orientation::Perspective, square_to_xy};
+
+/// How played moves are animated. A zero duration turns animations off.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct AnimationSettings {
+    pub duration: Duration,
+    pub easing: EaseFunction,
+}
+
+impl Default for AnimationSettings {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_millis(200),
+            easing: EaseFunction::CubicOut,
+        }
+    }
+}
+
+/// The square a piece was just dragged from and dropped somewhere else. It is already where it
+/// was dropped, so it doesn't need to slide there.
+#[derive(Resource, Default)]
+pub struct DroppedPiece(pub Option<Square>);
+
+/// A stand-in sprite showing a piece on its way to, or out of, the board. Pieces can't be moved
+/// while any of these exist.
+#[derive(Component)]
+pub struct PieceAnimation {
+    timer: Timer,
+    kind: AnimationKind,
+}
+
+enum AnimationKind {
+    /// `reveals` is the piece entity on `to`, hidden until the slide is over
+    Slide {
+        from: Square,
+        to: Square,
+        reveals: Entity,
+    },
+    /// a captured piece
+    Fade { on: Square },
+}
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnimationSettings>()
+            .init_resource::<DroppedPiece>()
+            .add_systems(Update, animate_pieces)
+            .add_observer(start_slide)
+            .add_observer(start_fade)
+            .add_observer(
+                |_: Trigger<MoveRejected>, mut dropped: ResMut<DroppedPiece>| {
+                    dropped.0 = None;
+                },
+            );
+    }
+}
+
+fn start_slide(
+    moved: Trigger<PieceMoved>,
+    settings: Res<AnimationSettings>,
+    piece_assets: Res<PieceAssets>,
+    perspective: Res<Perspective>,
+    mut dropped: ResMut<DroppedPiece>,
+    pieces: Query<(Entity, &Square)>,
+    mut commands: Commands,
+) {
+    let PieceMoved { from, to, piece } = *moved;
+    if dropped.0 == Some(from) {
+        dropped.0 = None;
+        return;
+    }
+    if settings.duration.is_zero() {
+        return;
+    }
+    let Some((reveals, _)) = pieces.iter().find(|(_, square)| **square == to) else {
+        return;
+    };
+
+    commands.entity(reveals).insert(Visibility::Hidden);
+    commands.spawn((
+        PieceAnimation {
+            timer: Timer::new(settings.duration, TimerMode::Once),
+            kind: AnimationKind::Slide { from, to, reveals },
+        },
+        Sprite::from_image(piece_assets.get_image(piece.piece, piece.color)),
+        // above the pieces standing still
+        Transform::from_translation(square_to_xy(from, perspective.0).extend(1.5)),
+        Pickable::IGNORE,
+    ));
+}
+
+fn start_fade(
+    captured: Trigger<PieceCaptured>,
+    settings: Res<AnimationSettings>,
+    piece_assets: Res<PieceAssets>,
+    perspective: Res<Perspective>,
+    mut commands: Commands,
+) {
+    if settings.duration.is_zero() {
+        return;
+    }
+
+    let PieceCaptured { square, piece } = *captured;
+    commands.spawn((
+        PieceAnimation {
+            timer: Timer::new(settings.duration, TimerMode::Once),
+            kind: AnimationKind::Fade { on: square },
+        },
+        Sprite::from_image(piece_assets.get_image(piece.piece, piece.color)),
+        // under the piece taking it
+        Transform::from_translation(square_to_xy(square, perspective.0).extend(0.9)),
+        Pickable::IGNORE,
+    ));
+}
+
+fn animate_pieces(
+    time: Res<Time>,
+    settings: Res<AnimationSettings>,
+    perspective: Res<Perspective>,
+    mut animations: Query<(Entity, &mut PieceAnimation, &mut Transform, &mut Sprite)>,
+    mut commands: Commands,
+) {
+    for (entity, mut animation, mut transform, mut sprite) in animations.iter_mut() {
+        animation.timer.tick(time.delta());
+        let progress = settings.easing.sample_clamped(animation.timer.fraction());
+
+        match animation.kind {
+            AnimationKind::Slide { from, to, reveals } => {
+                // squares are looked up every frame, the board may flip halfway
+                let from = square_to_xy(from, perspective.0);
+                let to = square_to_xy(to, perspective.0);
+                let z = transform.translation.z;
+                transform.translation = from.lerp(to, progress).extend(z);
+
+                if animation.timer.finished() {
+                    commands.entity(reveals).insert(Visibility::Inherited);
+                }
+            }
+            AnimationKind::Fade { on } => {
+                let z = transform.translation.z;
+                transform.translation = square_to_xy(on, perspective.0).extend(z);
+                sprite.color.set_alpha(1.0 - progress);
+            }
+        }
+
+        if animation.timer.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
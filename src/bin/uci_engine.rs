@@ -0,0 +1,287 @@
+//! The built-in search as a UCI engine on stdin/stdout, to play it against other engines in
+//! tournament managers such as cutechess-cli.
+
+use std::{
+    io::{self, BufRead},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use anyhow::Context;
+use bevy_game_2::chess_plugin::{Board, Color, Score, SearchInfo, SearchLimits, search};
+
+/// Kept back from the clock for the time it takes to send the move.
+const MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+
+struct Search {
+    thread: JoinHandle<()>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Search {
+    fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.thread.join();
+    }
+}
+
+#[derive(Default)]
+struct Engine {
+    board: Board,
+    previous_positions: Vec<Board>,
+    search: Option<Search>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut engine = Engine::default();
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("uci") => {
+                println!(
+                    "id name {} {}",
+                    env!("CARGO_PKG_NAME"),
+                    env!("CARGO_PKG_VERSION")
+                );
+                // castling is read and written as the king taking its rook in Chess960
+                // positions either way
+                println!("option name UCI_Chess960 type check default false");
+                println!("uciok");
+            }
+            Some("isready") => println!("readyok"),
+            Some("ucinewgame") => {
+                engine.stop();
+                engine.board = Board::default();
+                engine.previous_positions.clear();
+            }
+            Some("position") => {
+                engine.stop();
+                match parse_position(tokens) {
+                    Ok((board, previous_positions)) => {
+                        engine.board = board;
+                        engine.previous_positions = previous_positions;
+                    }
+                    Err(error) => println!("info string {error:#}"),
+                }
+            }
+            Some("go") => {
+                engine.stop();
+                let go = parse_go(tokens);
+                engine.go(go);
+            }
+            Some("stop") => engine.stop(),
+            Some("quit") => break,
+            // setoption, debug, register, ponderhit: nothing to configure
+            _ => {}
+        }
+    }
+
+    engine.stop();
+    Ok(())
+}
+
+impl Engine {
+    fn stop(&mut self) {
+        if let Some(search) = self.search.take() {
+            search.stop();
+        }
+    }
+
+    fn go(&mut self, go: Go) {
+        let board = self.board.clone();
+        let previous_positions = self.previous_positions.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let limits = SearchLimits {
+            max_depth: go.depth.unwrap_or(SearchLimits::default().max_depth),
+            time: go.time_for(board.side_to_move()),
+            stop: Some(stop.clone()),
+        };
+
+        let thread = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let result = search(&board, &previous_positions, &limits, |info| {
+                    println!("{}", info_line(&board, info));
+                });
+                // an infinite search may only answer once told to stop
+                while go.infinite && !stop.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(5));
+                }
+                match result.best_move {
+                    Some(mv) => println!("bestmove {}", board.uci(mv)),
+                    None => println!("bestmove 0000"),
+                }
+            })
+        };
+
+        self.search = Some(Search { thread, stop });
+    }
+}
+
+/// `position [startpos | fen <fen>] [moves <move>...]`, returning the position and the ones
+/// before it.
+fn parse_position<'a>(
+    mut tokens: impl Iterator<Item = &'a str>,
+) -> anyhow::Result<(Board, Vec<Board>)> {
+    let mut board = match tokens.next() {
+        Some("startpos") => {
+            // skip up to "moves"
+            tokens.next();
+            Board::default()
+        }
+        Some("fen") => {
+            let fen: Vec<_> = tokens
+                .by_ref()
+                .take_while(|token| *token != "moves")
+                .collect();
+            let fen = fen.join(" ");
+            Board::from_fen(&fen).with_context(|| format!("invalid FEN: {fen}"))?
+        }
+        _ => anyhow::bail!("position needs startpos or fen"),
+    };
+
+    let mut previous_positions = Vec::new();
+    for uci in tokens {
+        let mv = board
+            .parse_uci(uci)
+            .with_context(|| format!("invalid move: {uci}"))?;
+        if let Err(reason) = board.validate_move(mv) {
+            anyhow::bail!("illegal move {uci}: {reason:?}");
+        }
+        previous_positions.push(board.clone());
+        board.play_unchecked(mv);
+    }
+
+    Ok((board, previous_positions))
+}
+
+#[derive(Default, Debug, PartialEq)]
+struct Go {
+    wtime: Option<Duration>,
+    btime: Option<Duration>,
+    winc: Duration,
+    binc: Duration,
+    moves_to_go: Option<u32>,
+    move_time: Option<Duration>,
+    depth: Option<u8>,
+    infinite: bool,
+}
+
+fn parse_go<'a>(mut tokens: impl Iterator<Item = &'a str>) -> Go {
+    let mut go = Go::default();
+    let millis = |value: Option<&str>| {
+        value
+            .and_then(|value| value.parse::<i64>().ok())
+            // some GUIs send negative times once a player is low
+            .map(|value| Duration::from_millis(value.max(0) as u64))
+    };
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "wtime" => go.wtime = millis(tokens.next()),
+            "btime" => go.btime = millis(tokens.next()),
+            "winc" => go.winc = millis(tokens.next()).unwrap_or_default(),
+            "binc" => go.binc = millis(tokens.next()).unwrap_or_default(),
+            "movestogo" => go.moves_to_go = tokens.next().and_then(|value| value.parse().ok()),
+            "movetime" => go.move_time = millis(tokens.next()),
+            "depth" => go.depth = tokens.next().and_then(|value| value.parse().ok()),
+            "infinite" => go.infinite = true,
+            _ => {}
+        }
+    }
+    go
+}
+
+impl Go {
+    /// How long `color` should think, or `None` to search until told to stop.
+    fn time_for(&self, color: Color) -> Option<Duration> {
+        if self.infinite {
+            return None;
+        }
+        if let Some(move_time) = self.move_time {
+            return Some(move_time.saturating_sub(MOVE_OVERHEAD));
+        }
+
+        let (remaining, increment) = match color {
+            Color::White => (self.wtime?, self.winc),
+            Color::Black => (self.btime?, self.binc),
+        };
+        // spread the clock over the moves left, assuming a long game when the GUI doesn't say
+        let budget = remaining / self.moves_to_go.unwrap_or(30).max(1) + increment * 3 / 4;
+        Some(
+            budget
+                .min(remaining.saturating_sub(MOVE_OVERHEAD))
+                .max(Duration::from_millis(1)),
+        )
+    }
+}
+
+fn info_line(board: &Board, info: &SearchInfo) -> String {
+    let score = match info.score {
+        Score::Centipawns(centipawns) => format!("cp {centipawns}"),
+        Score::Mate(moves) => format!("mate {moves}"),
+    };
+    let millis = info.elapsed.as_millis();
+    let nps = info.nodes as u128 * 1000 / millis.max(1);
+
+    let mut position = board.clone();
+    let pv: Vec<_> = info
+        .pv
+        .iter()
+        .map(|mv| {
+            let uci = position.uci(*mv);
+            position.play_unchecked(*mv);
+            uci
+        })
+        .collect();
+
+    format!(
+        "info depth {} score {score} nodes {} nps {nps} time {millis} pv {}",
+        info.depth,
+        info.nodes,
+        pv.join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_positions_and_search_limits() {
+        let (board, previous_positions) =
+            parse_position("startpos moves e2e4 e7e5 e1e2".split_whitespace()).unwrap();
+        assert_eq!(previous_positions.len(), 3);
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPPKPPP/RNBQ1BNR b kq - 1 2"
+        );
+        assert!(parse_position("fen 8/8/8 w - - 0 1".split_whitespace()).is_err());
+
+        let go = parse_go("wtime 60000 btime 1000 winc 1000 binc 0".split_whitespace());
+        assert_eq!(go.time_for(Color::White), Some(Duration::from_millis(2750)));
+        assert_eq!(
+            go.time_for(Color::Black),
+            Some(Duration::from_millis(1000) / 30)
+        );
+        assert_eq!(
+            parse_go("movetime 500".split_whitespace()).time_for(Color::Black),
+            Some(Duration::from_millis(470))
+        );
+    }
+
+    #[test]
+    fn rejects_illegal_moves() {
+        // nothing on a3, and a pawn doesn't go three squares
+        for moves in ["a3a4", "e2e5"] {
+            let position = format!("startpos moves {moves}");
+            assert!(parse_position(position.split_whitespace()).is_err());
+        }
+    }
+}
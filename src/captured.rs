@@ -0,0 +1,112 @@
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_game_2::chess_plugin::{self, Board, MoveHistory};
+
+use crate::{BOARD_CENTER, PIECE_SPRITE_SIZE, PieceAssets, orientation::Perspective};
+
+const ICON_SIZE: f32 = PIECE_SPRITE_SIZE * 0.35;
+/// how far apart pieces of the same type are, overlapping each other
+const STACK_STEP: f32 = ICON_SIZE * 0.35;
+const GROUP_GAP: f32 = ICON_SIZE * 0.3;
+
+/// Beside the board, the pieces a side captured in the [`MoveHistory`], grouped by type, and how
+/// far ahead in material it is. Rebuilt whenever the board changes. Variants with pockets show
+/// those instead.
+#[derive(Component)]
+pub struct CapturedTray(pub chess_plugin::Color);
+
+pub struct CapturedPlugin;
+
+impl Plugin for CapturedPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_captured_trays).add_systems(
+            Update,
+            update_captured_trays.run_if(
+                resource_changed::<Board>
+                    .or(resource_changed::<Perspective>)
+                    .or(resource_changed::<PieceAssets>),
+            ),
+        );
+    }
+}
+
+/// Where the tray of `color` starts, just inside its clock.
+fn tray_position(color: chess_plugin::Color, perspective: chess_plugin::Color) -> Vec2 {
+    let y = if color == perspective {
+        -PIECE_SPRITE_SIZE * 2.5
+    } else {
+        PIECE_SPRITE_SIZE * 2.5
+    };
+    BOARD_CENTER + Vec2::new(PIECE_SPRITE_SIZE * 4.3 + ICON_SIZE * 0.5, y)
+}
+
+fn spawn_captured_trays(mut commands: Commands) {
+    for color in [chess_plugin::Color::White, chess_plugin::Color::Black] {
+        commands.spawn((
+            CapturedTray(color),
+            Transform::default(),
+            Visibility::default(),
+        ));
+    }
+}
+
+fn update_captured_trays(
+    board: Res<Board>,
+    history: Res<MoveHistory>,
+    perspective: Res<Perspective>,
+    piece_assets: Res<PieceAssets>,
+    mut trays: Query<(Entity, &CapturedTray, &mut Transform)>,
+    mut commands: Commands,
+) {
+    let balance = board.material(chess_plugin::Color::White) as i32
+        - board.material(chess_plugin::Color::Black) as i32;
+
+    for (entity, tray, mut transform) in trays.iter_mut() {
+        transform.translation = tray_position(tray.0, perspective.0).extend(0.0);
+
+        let lost = tray.0.opposite();
+        let ahead = match tray.0 {
+            chess_plugin::Color::White => balance,
+            chess_plugin::Color::Black => -balance,
+        };
+
+        commands.entity(entity).despawn_related::<Children>();
+        if board.variant().has_pockets() {
+            continue;
+        }
+
+        commands.entity(entity).with_children(|tray| {
+            let mut x = 0.0;
+            for (piece, count) in history.captured(lost) {
+                if count == 0 {
+                    continue;
+                }
+                for index in 0..count {
+                    tray.spawn((
+                        Sprite {
+                            image: piece_assets.get_image(piece, lost),
+                            custom_size: Some(Vec2::splat(ICON_SIZE)),
+                            ..Default::default()
+                        },
+                        // later pieces of a group go on top of earlier ones
+                        Transform::from_xyz(x, 0.0, f32::from(index) * 0.01),
+                        Pickable::IGNORE,
+                    ));
+                    x += STACK_STEP;
+                }
+                x += ICON_SIZE - STACK_STEP + GROUP_GAP;
+            }
+
+            if ahead > 0 {
+                tray.spawn((
+                    Text2d::new(format!("+{ahead}")),
+                    TextFont {
+                        font_size: ICON_SIZE * 0.6,
+                        ..Default::default()
+                    },
+                    Anchor::CenterLeft,
+                    Transform::from_xyz(x - ICON_SIZE * 0.5, 0.0, 0.0),
+                ));
+            }
+        });
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task, block_on, poll_once},
+};
+
+use super::{Board, Color, GameResult, MoveHistory, MoveRequest};
+
+mod eval;
+mod search;
+pub use search::*;
+
+/// Lets the computer play `color`. Spawn it as its own entity; it searches whenever it is
+/// `color`'s turn and plays the result through a [`MoveRequest`]. The search knows only the
+/// standard rules, so it sits out any other [`super::ChessVariant`].
+#[derive(Component, Clone, Debug)]
+pub struct AiPlayer {
+    pub color: Color,
+    pub max_depth: u8,
+    /// how long to think about each move
+    pub time_limit: Option<Duration>,
+}
+
+impl AiPlayer {
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            max_depth: 64,
+            time_limit: Some(Duration::from_secs(1)),
+        }
+    }
+}
+
+#[derive(Component)]
+struct AiThinking {
+    task: Task<Option<MoveRequest>>,
+    /// the position being searched
+    position: Board,
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for AiThinking {
+    fn drop(&mut self) {
+        // the search runs on a thread of its own, and would otherwise run out its time
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn start_thinking(
+    players: Query<(Entity, &AiPlayer), Without<AiThinking>>,
+    board: Res<Board>,
+    history: Res<MoveHistory>,
+    result: Res<GameResult>,
+    mut commands: Commands,
+) {
+    if result.is_over() {
+        return;
+    }
+    if !board.variant().standard_rules() {
+        warn_once!(
+            "the AI only plays standard chess, not {}",
+            board.variant().name()
+        );
+        return;
+    }
+
+    for (entity, player) in players.iter() {
+        if player.color != board.side_to_move() {
+            continue;
+        }
+
+        let position = board.clone();
+        let previous_positions: Vec<Board> = history
+            .played()
+            .iter()
+            .map(|played| played.before.clone())
+            .collect();
+        let stop = Arc::new(AtomicBool::new(false));
+        let limits = SearchLimits {
+            max_depth: player.max_depth,
+            time: player.time_limit,
+            stop: Some(stop.clone()),
+        };
+
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            search(&position, &previous_positions, &limits, |_| {}).best_move
+        });
+
+        commands.entity(entity).insert(AiThinking {
+            task,
+            position: board.clone(),
+            stop,
+        });
+    }
+}
+
+fn finish_thinking(
+    mut thinking: Query<(Entity, &mut AiThinking)>,
+    board: Res<Board>,
+    result: Res<GameResult>,
+    mut commands: Commands,
+) {
+    for (entity, mut thinking) in thinking.iter_mut() {
+        // the game moved on without us (undo, new game, ...), so the search is moot
+        if thinking.position != *board || result.is_over() {
+            commands.entity(entity).remove::<AiThinking>();
+            continue;
+        }
+
+        let Some(best_move) = block_on(poll_once(&mut thinking.task)) else {
+            continue;
+        };
+        commands.entity(entity).remove::<AiThinking>();
+
+        if let Some(mv) = best_move {
+            commands.trigger(mv);
+        }
+    }
+}
+
+pub(super) fn setup_ai(app: &mut App) {
+    app.add_systems(Update, (finish_thinking, start_thinking).chain());
+}
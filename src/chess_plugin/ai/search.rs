@@ -0,0 +1,515 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use cozy_chess::{Move, Piece};
+
+use super::eval::{PIECE_VALUES, evaluate};
+use crate::chess_plugin::{Board, MoveRequest};
+
+const INFINITY: i32 = 32_000;
+const MATE: i32 = 30_000;
+const MAX_PLY: i32 = 128;
+// scores beyond this are mates, and carry their distance from the root
+const MATE_BOUND: i32 = MATE - MAX_PLY;
+
+const TRANSPOSITION_TABLE_ENTRIES: usize = 1 << 18;
+
+#[derive(Clone, Debug)]
+pub struct SearchLimits {
+    pub max_depth: u8,
+    pub time: Option<Duration>,
+    /// stops the search as soon as it is set, keeping the best move found so far
+    pub stop: Option<Arc<AtomicBool>>,
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 64,
+            time: None,
+            stop: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Score {
+    Centipawns(i32),
+    /// mate in this many moves, negative when the side to move gets mated
+    Mate(i32),
+}
+
+impl Score {
+    fn from_internal(score: i32) -> Self {
+        if score > MATE_BOUND {
+            Score::Mate((MATE - score + 1) / 2)
+        } else if score < -MATE_BOUND {
+            Score::Mate(-(MATE + score) / 2)
+        } else {
+            Score::Centipawns(score)
+        }
+    }
+}
+
+/// Progress reported after each completed iteration.
+#[derive(Clone, Debug)]
+pub struct SearchInfo {
+    pub depth: u8,
+    pub score: Score,
+    pub nodes: u64,
+    pub elapsed: Duration,
+    pub pv: Vec<MoveRequest>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    /// `None` only when there are no legal moves
+    pub best_move: Option<MoveRequest>,
+    pub info: Option<SearchInfo>,
+}
+
+/// Iterative deepening alpha-beta search with quiescence and a transposition table.
+/// `previous_positions` are the positions of the game so far, to recognize repetitions.
+pub fn search(
+    board: &Board,
+    previous_positions: &[Board],
+    limits: &SearchLimits,
+    mut on_info: impl FnMut(&SearchInfo),
+) -> SearchResult {
+    let start = Instant::now();
+    let mut searcher = Searcher {
+        table: TranspositionTable::new(),
+        nodes: 0,
+        deadline: limits.time.map(|time| start + time),
+        stop: limits.stop.clone(),
+        stopped: false,
+        path: previous_positions
+            .iter()
+            .map(|board| board.position.hash())
+            .collect(),
+    };
+
+    let mut root_moves = legal_moves(&board.position);
+    let mut result = SearchResult {
+        best_move: root_moves.first().map(|mv| (*mv).into()),
+        info: None,
+    };
+
+    for depth in 1..=limits.max_depth.max(1) {
+        let Some((best_move, score)) = searcher.root(&board.position, &mut root_moves, depth)
+        else {
+            // an unfinished iteration is thrown away, the previous one's move stands
+            break;
+        };
+
+        let info = SearchInfo {
+            depth,
+            score: Score::from_internal(score),
+            nodes: searcher.nodes,
+            elapsed: start.elapsed(),
+            pv: searcher.principal_variation(&board.position, depth),
+        };
+        on_info(&info);
+        result = SearchResult {
+            best_move: Some(best_move.into()),
+            info: Some(info),
+        };
+
+        // nothing left to find once a forced mate is seen
+        if score.abs() > MATE_BOUND {
+            break;
+        }
+    }
+
+    result
+}
+
+#[derive(Clone, Copy)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    hash: u64,
+    depth: u8,
+    score: i32,
+    bound: Bound,
+    best_move: Option<Move>,
+}
+
+struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+}
+
+impl TranspositionTable {
+    fn new() -> Self {
+        Self {
+            entries: vec![None; TRANSPOSITION_TABLE_ENTRIES],
+        }
+    }
+
+    fn probe(&self, hash: u64) -> Option<Entry> {
+        self.entries[hash as usize % self.entries.len()].filter(|entry| entry.hash == hash)
+    }
+
+    fn store(&mut self, entry: Entry) {
+        let index = entry.hash as usize % self.entries.len();
+        self.entries[index] = Some(entry);
+    }
+}
+
+struct Searcher {
+    table: TranspositionTable,
+    nodes: u64,
+    deadline: Option<Instant>,
+    stop: Option<Arc<AtomicBool>>,
+    stopped: bool,
+    /// hashes of the positions leading to the current one
+    path: Vec<u64>,
+}
+
+impl Searcher {
+    fn should_stop(&mut self) -> bool {
+        // checking the clock on every node is wasteful
+        if !self.stopped && self.nodes % 1024 == 0 {
+            let out_of_time = self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+            let stopped = self
+                .stop
+                .as_ref()
+                .is_some_and(|stop| stop.load(Ordering::Relaxed));
+            self.stopped = out_of_time || stopped;
+        }
+        self.stopped
+    }
+
+    fn root(
+        &mut self,
+        board: &cozy_chess::Board,
+        moves: &mut [Move],
+        depth: u8,
+    ) -> Option<(Move, i32)> {
+        let tt_move = self
+            .table
+            .probe(board.hash())
+            .and_then(|entry| entry.best_move);
+        order_moves(board, moves, tt_move);
+
+        let mut alpha = -INFINITY;
+        let mut best = None;
+        self.path.push(board.hash());
+        for mv in moves.iter() {
+            let mut child = board.clone();
+            child.play_unchecked(*mv);
+            let score = -self.negamax(&child, depth - 1, 1, -INFINITY, -alpha);
+            if self.stopped {
+                break;
+            }
+            if score > alpha {
+                alpha = score;
+                best = Some((*mv, score));
+            }
+        }
+        self.path.pop();
+
+        if self.stopped {
+            return None;
+        }
+        let (best_move, score) = best?;
+        self.table.store(Entry {
+            hash: board.hash(),
+            depth,
+            score,
+            bound: Bound::Exact,
+            best_move: Some(best_move),
+        });
+        Some((best_move, score))
+    }
+
+    fn negamax(
+        &mut self,
+        board: &cozy_chess::Board,
+        depth: u8,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        if self.should_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        let hash = board.hash();
+        if board.halfmove_clock() >= 100 || self.is_repetition(hash, board.halfmove_clock()) {
+            return 0;
+        }
+
+        let in_check = !board.checkers().is_empty();
+        // never stand pat while in check
+        let depth = match (depth, in_check) {
+            (0, false) => return self.quiescence(board, ply, alpha, beta),
+            (0, true) => 1,
+            (depth, _) => depth,
+        };
+
+        let tt_entry = self.table.probe(hash);
+        if let Some(entry) = tt_entry {
+            if entry.depth >= depth {
+                let score = score_from_table(entry.score, ply);
+                let usable = match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => score >= beta,
+                    Bound::Upper => score <= alpha,
+                };
+                if usable {
+                    return score;
+                }
+            }
+        }
+
+        let mut moves = legal_moves(board);
+        if moves.is_empty() {
+            return if in_check { -MATE + ply } else { 0 };
+        }
+        if ply >= MAX_PLY {
+            return evaluate(board);
+        }
+        order_moves(
+            board,
+            &mut moves,
+            tt_entry.and_then(|entry| entry.best_move),
+        );
+
+        let original_alpha = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+        self.path.push(hash);
+        for mv in moves {
+            let mut child = board.clone();
+            child.play_unchecked(mv);
+            let score = -self.negamax(&child, depth - 1, ply + 1, -beta, -alpha);
+            if self.stopped {
+                break;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(mv);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        self.path.pop();
+
+        if self.stopped {
+            return 0;
+        }
+
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.store(Entry {
+            hash,
+            depth,
+            score: score_to_table(best_score, ply),
+            bound,
+            best_move,
+        });
+
+        best_score
+    }
+
+    // only captures and promotions, so the evaluation isn't taken in the middle of an exchange
+    fn quiescence(
+        &mut self,
+        board: &cozy_chess::Board,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        if self.should_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        let stand_pat = evaluate(board);
+        if stand_pat >= beta || ply >= MAX_PLY {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        let enemies = board.colors(!board.side_to_move());
+        let mut moves = Vec::new();
+        board.generate_moves(|piece_moves| {
+            for mv in piece_moves {
+                if enemies.has(mv.to) || mv.promotion == Some(Piece::Queen) {
+                    moves.push(mv);
+                }
+            }
+            false
+        });
+        order_moves(board, &mut moves, None);
+
+        for mv in moves {
+            let mut child = board.clone();
+            child.play_unchecked(mv);
+            let score = -self.quiescence(&child, ply + 1, -beta, -alpha);
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+
+        alpha
+    }
+
+    fn is_repetition(&self, hash: u64, halfmove_clock: u8) -> bool {
+        // positions before the last capture or pawn move can't come back
+        self.path
+            .iter()
+            .rev()
+            .take(usize::from(halfmove_clock))
+            .any(|previous| *previous == hash)
+    }
+
+    fn principal_variation(&self, board: &cozy_chess::Board, depth: u8) -> Vec<MoveRequest> {
+        let mut pv = Vec::new();
+        let mut board = board.clone();
+        while pv.len() < usize::from(depth) {
+            let Some(mv) = self
+                .table
+                .probe(board.hash())
+                .and_then(|entry| entry.best_move)
+                .filter(|mv| board.is_legal(*mv))
+            else {
+                break;
+            };
+            board.play_unchecked(mv);
+            pv.push(mv.into());
+        }
+        pv
+    }
+}
+
+fn legal_moves(board: &cozy_chess::Board) -> Vec<Move> {
+    let mut moves = Vec::new();
+    board.generate_moves(|piece_moves| {
+        moves.extend(piece_moves);
+        false
+    });
+    moves
+}
+
+// the table's move first, then captures by most valuable victim and least valuable attacker
+fn order_moves(board: &cozy_chess::Board, moves: &mut [Move], tt_move: Option<Move>) {
+    let enemies = board.colors(!board.side_to_move());
+    moves.sort_by_cached_key(|mv| {
+        if Some(*mv) == tt_move {
+            return i32::MIN;
+        }
+        let mut score = 0;
+        if enemies.has(mv.to) {
+            let victim = board
+                .piece_on(mv.to)
+                .map_or(0, |piece| PIECE_VALUES[piece as usize]);
+            let attacker = board
+                .piece_on(mv.from)
+                .map_or(0, |piece| PIECE_VALUES[piece as usize]);
+            score += 10_000 + 10 * victim - attacker;
+        }
+        if let Some(promotion) = mv.promotion {
+            score += PIECE_VALUES[promotion as usize];
+        }
+        -score
+    });
+}
+
+// mate scores are stored relative to the node, not the root
+fn score_to_table(score: i32, ply: i32) -> i32 {
+    if score > MATE_BOUND {
+        score + ply
+    } else if score < -MATE_BOUND {
+        score - ply
+    } else {
+        score
+    }
+}
+fn score_from_table(score: i32, ply: i32) -> i32 {
+    if score > MATE_BOUND {
+        score - ply
+    } else if score < -MATE_BOUND {
+        score + ply
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess_plugin::Square;
+
+    fn best_move(fen: &str, max_depth: u8) -> SearchResult {
+        let board = Board::from_fen(fen).unwrap();
+        search(
+            &board,
+            &[],
+            &SearchLimits {
+                max_depth,
+                ..Default::default()
+            },
+            |_| {},
+        )
+    }
+
+    #[test]
+    fn finds_mate_in_one() {
+        let result = best_move("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1", 3);
+        assert_eq!(
+            result.best_move,
+            Some(MoveRequest::Move {
+                from: Square::A1,
+                to: Square::A8,
+                promotion: None,
+            })
+        );
+        assert_eq!(result.info.unwrap().score, Score::Mate(1));
+    }
+
+    #[test]
+    fn takes_a_hanging_queen() {
+        let result = best_move("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", 4);
+        assert_eq!(
+            result.best_move,
+            Some(MoveRequest::Move {
+                from: Square::D2,
+                to: Square::D5,
+                promotion: None,
+            })
+        );
+    }
+
+    #[test]
+    fn reports_no_move_when_mated() {
+        let result = best_move("R5k1/5ppp/8/8/8/8/5PPP/6K1 b - - 0 1", 3);
+        assert_eq!(result.best_move, None);
+    }
+}
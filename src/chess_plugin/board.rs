@@ -1,43 +1,770 @@
+use std::sync::Arc;
+
 use bevy::ecs::resource::Resource;
 
-use super::{Color, GameStatus, MoveRequest, Piece, Square};
+use super::{
+    ChessVariant, Color, GameStatus, MoveRequest, Piece, Rank, RejectionReason, Square, Standard,
+};
+
+/// A position, and the [`ChessVariant`] whose rules it is played by.
+#[derive(Resource, Clone)]
+pub struct Board {
+    pub(super) position: cozy_chess::Board,
+    variant: Arc<dyn ChessVariant>,
+    /// how many times each side gave check, white's first
+    checks: [u8; 2],
+    /// the pieces each side can drop, white's first, by [`POCKET_PIECES`]; only ever filled in
+    /// variants with pockets
+    pockets: [[u8; 5]; 2],
+    /// the pieces that were pawns before promoting, which go back into a pocket as pawns
+    promoted: cozy_chess::BitBoard,
+}
 
-#[derive(Resource, Default)]
-pub struct Board(cozy_chess::Board);
+/// The pieces a pocket can hold, in the order they are listed.
+pub const POCKET_PIECES: [Piece; 5] = [
+    Piece::Pawn,
+    Piece::Knight,
+    Piece::Bishop,
+    Piece::Rook,
+    Piece::Queen,
+];
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::standard(cozy_chess::Board::default())
+    }
+}
+
+/// Two boards are equal when their positions are, whatever variant either is played by.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position
+            && self.checks == other.checks
+            && self.pockets == other.pockets
+    }
+}
+
+impl Eq for Board {}
 
 impl Board {
+    fn standard(position: cozy_chess::Board) -> Self {
+        Self {
+            position,
+            variant: Arc::new(Standard),
+            checks: [0; 2],
+            pockets: [[0; 5]; 2],
+            promoted: cozy_chess::BitBoard::EMPTY,
+        }
+    }
+    /// Reads a FEN, or a Shredder-FEN whose castling rights name the rooks' files, as Chess960
+    /// positions need. The position is played by the standard rules until
+    /// [`Board::with_variant`] says otherwise.
+    pub fn from_fen(fen: &str) -> Result<Self, cozy_chess::FenParseError> {
+        cozy_chess::Board::from_fen(fen, false)
+            .or_else(|_| cozy_chess::Board::from_fen(fen, true))
+            .map(Self::standard)
+    }
+    /// The same position, played by the rules of `variant`.
+    pub fn with_variant(self, variant: Arc<dyn ChessVariant>) -> Self {
+        Self { variant, ..self }
+    }
+    pub fn variant(&self) -> &Arc<dyn ChessVariant> {
+        &self.variant
+    }
+    /// FEN, or Shredder-FEN when the castling rights can't be told apart with KQkq.
+    pub fn to_fen(&self) -> String {
+        if self.is_chess960() {
+            self.to_shredder_fen()
+        } else {
+            self.position.to_string()
+        }
+    }
+    /// Shredder-FEN, the castling rights naming the files of the rooks, like `HAha`.
+    pub fn to_shredder_fen(&self) -> String {
+        format!("{:#}", self.position)
+    }
+    /// The Chess960 starting position numbered `index`, from 0 to 959, 518 being the standard
+    /// one.
+    pub fn chess960(index: u32) -> Option<Self> {
+        (index < 960).then(|| Self::standard(cozy_chess::Board::chess960_startpos(index)))
+    }
+    /// Whether castling in this position goes beyond what standard chess allows: a king off the
+    /// e-file or a rook off the corners that can still castle.
+    pub fn is_chess960(&self) -> bool {
+        use cozy_chess::File;
+
+        [cozy_chess::Color::White, cozy_chess::Color::Black]
+            .into_iter()
+            .any(|color| {
+                let rights = self.position.castle_rights(color);
+                let can_castle = rights.short.is_some() || rights.long.is_some();
+                (can_castle && self.position.king(color).file() != File::E)
+                    || rights.short.is_some_and(|file| file != File::H)
+                    || rights.long.is_some_and(|file| file != File::A)
+            })
+    }
     pub fn piece_on(&self, square: Square) -> Option<Piece> {
-        self.0.piece_on(square.into()).map(Into::into)
+        self.position.piece_on(square.into()).map(Into::into)
     }
     pub fn color_on(&self, square: Square) -> Option<Color> {
-        self.0.color_on(square.into()).map(Into::into)
+        self.position.color_on(square.into()).map(Into::into)
     }
+    /// Whether `mv` is one of the [`Board::legal_moves`]. Castling can also be asked for as the
+    /// king taking its own rook.
     pub fn is_legal(&self, mv: MoveRequest) -> bool {
-        self.0.is_legal(mv.into())
+        self.legal_moves().contains(&self.canonical(mv))
+    }
+    /// `mv` as the [`Board::legal_moves`] list it, so that castling asked for as the king taking
+    /// its own rook or as the king moving two squares is played and recorded one way.
+    pub fn canonical(&self, mv: MoveRequest) -> MoveRequest {
+        match mv {
+            MoveRequest::Move {
+                from,
+                to,
+                promotion,
+            } => self.to_request(self.to_move(from, to, promotion)),
+            drop @ MoveRequest::Drop { .. } => drop,
+        }
+    }
+    /// Why `mv` can't be played in this position, if it can't. Whether the game is already over
+    /// is not the board's concern.
+    pub fn validate_move(&self, mv: MoveRequest) -> Result<(), RejectionReason> {
+        let (from, to, promotion) = match mv {
+            MoveRequest::Move {
+                from,
+                to,
+                promotion,
+            } => (from, to, promotion),
+            MoveRequest::Drop { piece, to } => return self.validate_drop(piece, to),
+        };
+        let Some(color) = self.color_on(from) else {
+            return Err(RejectionReason::NoPieceOnSource);
+        };
+        if color != self.side_to_move() {
+            return Err(RejectionReason::WrongSideToMove);
+        }
+        if self.is_legal(mv) {
+            return Ok(());
+        }
+        if self.position.is_legal(self.to_move(from, to, promotion)) {
+            // left out by the variant
+            return Err(RejectionReason::IllegalMove);
+        }
+        if self.needs_promotion(mv) {
+            return Err(RejectionReason::MissingPromotion);
+        }
+        if self.is_pseudo_legal(self.to_move(from, to, promotion)) {
+            return Err(RejectionReason::LeavesKingInCheck);
+        }
+        Err(RejectionReason::IllegalMove)
+    }
+    fn validate_drop(&self, piece: Piece, to: Square) -> Result<(), RejectionReason> {
+        if self.pocket_count(self.side_to_move(), piece) == 0 {
+            return Err(RejectionReason::NoPieceOnSource);
+        }
+        if self.is_legal(MoveRequest::Drop { piece, to }) {
+            return Ok(());
+        }
+        if self.dropped(piece, to).is_some() {
+            // left out by the variant
+            return Err(RejectionReason::IllegalMove);
+        }
+        let back_rank = matches!(to.rank(), Rank::First | Rank::Eighth);
+        if self.piece_on(to).is_some() || (piece == Piece::Pawn && back_rank) {
+            return Err(RejectionReason::IllegalMove);
+        }
+        Err(RejectionReason::LeavesKingInCheck)
+    }
+    /// Every legal move by the rules of the variant, as [`ChessVariant::legal_moves`] lists them.
+    pub fn legal_moves(&self) -> Vec<MoveRequest> {
+        self.variant.legal_moves(self)
+    }
+    /// Every legal move by the standard rules, castling as the king moving two squares, and
+    /// promotions once for each piece, then every piece of the side to move's pocket on every
+    /// empty square it may be dropped on.
+    pub fn standard_legal_moves(&self) -> Vec<MoveRequest> {
+        let mut moves = Vec::new();
+        self.position.generate_moves(|piece_moves| {
+            moves.extend(piece_moves.into_iter().map(|mv| self.to_request(mv)));
+            false
+        });
+
+        let color = self.side_to_move();
+        let drops = POCKET_PIECES
+            .into_iter()
+            .filter(|piece| self.pocket_count(color, *piece) > 0)
+            .flat_map(|piece| {
+                (!self.position.occupied())
+                    .into_iter()
+                    .map(move |to| (piece, Square::from(to)))
+            })
+            .filter(|(piece, to)| self.dropped(*piece, *to).is_some())
+            .map(|(piece, to)| MoveRequest::Drop { piece, to });
+        moves.extend(drops);
+        moves
+    }
+    /// The legal drops among the [`Board::legal_moves`].
+    pub fn legal_drops(&self) -> Vec<MoveRequest> {
+        self.legal_moves()
+            .into_iter()
+            .filter(|mv| matches!(mv, MoveRequest::Drop { .. }))
+            .collect()
+    }
+    /// What `color` can drop, by type.
+    pub fn pocket(&self, color: Color) -> [(Piece, u8); 5] {
+        let pocket = self.pockets[color as usize];
+        std::array::from_fn(|index| (POCKET_PIECES[index], pocket[index]))
+    }
+    /// How many of `piece` `color` can drop.
+    pub fn pocket_count(&self, color: Color, piece: Piece) -> u8 {
+        POCKET_PIECES
+            .iter()
+            .position(|pocketed| *pocketed == piece)
+            .map_or(0, |index| self.pockets[color as usize][index])
+    }
+    /// The [`Board::legal_moves`] of the piece on `square`.
+    pub fn legal_moves_from(&self, square: Square) -> Vec<MoveRequest> {
+        self.legal_moves()
+            .into_iter()
+            .filter(|mv| mv.source() == Some(square))
+            .collect()
+    }
+    /// Whether `mv` takes a piece, en passant included.
+    pub fn is_capture(&self, mv: MoveRequest) -> bool {
+        let MoveRequest::Move {
+            from,
+            to,
+            promotion,
+        } = mv
+        else {
+            return false;
+        };
+        let mv = self.to_move(from, to, promotion);
+        let color = self.position.side_to_move();
+        if self.position.colors(!color).has(mv.to) {
+            return true;
+        }
+        self.position.piece_on(mv.from) == Some(cozy_chess::Piece::Pawn)
+            && mv.from.file() != mv.to.file()
     }
     pub fn play_unchecked(&mut self, mv: MoveRequest) {
-        self.0.play_unchecked(mv.into());
+        let color = self.side_to_move();
+        match mv {
+            MoveRequest::Move {
+                from,
+                to,
+                promotion,
+            } => {
+                let mv = self.to_move(from, to, promotion);
+                if self.variant.has_pockets() {
+                    self.pocket_capture(mv);
+                }
+                self.move_promoted(mv);
+                self.position.play_unchecked(mv);
+            }
+            MoveRequest::Drop { piece, to } => {
+                self.position = self.dropped(piece, to).expect("a legal drop");
+                let index = POCKET_PIECES.iter().position(|pocketed| *pocketed == piece);
+                self.pockets[color as usize][index.expect("a pocket piece")] -= 1;
+            }
+        }
+        if self.is_check() {
+            self.checks[color as usize] += 1;
+        }
     }
+    /// How many times `color` gave check since the game started.
+    pub fn checks_given(&self, color: Color) -> u8 {
+        self.checks[color as usize]
+    }
+    /// Standard Algebraic Notation for `mv`, which must be legal in this position. Drops are
+    /// written like `N@f3`, with the check mark as for other moves.
+    pub fn san(&self, mv: MoveRequest) -> String {
+        let MoveRequest::Move {
+            from,
+            to,
+            promotion,
+        } = mv
+        else {
+            let mut after = self.clone();
+            after.play_unchecked(mv);
+            let check = match (after.is_check(), after.has_legal_moves()) {
+                (false, _) => "",
+                (true, true) => "+",
+                (true, false) => "#",
+            };
+            return format!("{}{check}", display_drop(mv));
+        };
+        cozy_chess::util::display_san_move(&self.position, self.to_move(from, to, promotion))
+            .to_string()
+    }
+    /// Reads SAN, drops included.
+    pub fn parse_san(&self, san: &str) -> Result<MoveRequest, cozy_chess::MoveParseError> {
+        if let Some(drop) = parse_drop(san.trim_end_matches(['+', '#'])) {
+            return self
+                .is_legal(drop)
+                .then_some(drop)
+                .ok_or(cozy_chess::MoveParseError);
+        }
+        cozy_chess::util::parse_san_move(&self.position, san).map(Into::into)
+    }
+    /// UCI long algebraic notation for `mv`, castling as the king moving two squares, or onto
+    /// the rook in Chess960 positions as engines expect there. Drops are written like `N@f3`.
+    pub fn uci(&self, mv: MoveRequest) -> String {
+        let MoveRequest::Move {
+            from,
+            to,
+            promotion,
+        } = mv
+        else {
+            return display_drop(mv);
+        };
+        let mv = self.to_move(from, to, promotion);
+        if self.is_chess960() {
+            mv.to_string()
+        } else {
+            cozy_chess::util::display_uci_move(&self.position, mv).to_string()
+        }
+    }
+    pub fn parse_uci(&self, uci: &str) -> Result<MoveRequest, cozy_chess::MoveParseError> {
+        if uci.contains('@') {
+            return parse_drop(uci).ok_or(cozy_chess::MoveParseError);
+        }
+        cozy_chess::util::parse_uci_move(&self.position, uci).map(Into::into)
+    }
+    /// Whether the game goes on, by the rules of the variant.
     pub fn status(&self) -> GameStatus {
-        self.0.status().into()
+        self.variant.status(self)
+    }
+    /// Whether the game goes on by the standard rules: checkmate, stalemate and the fifty-move
+    /// rule, for variants to build on. Having no moves goes by the variant's
+    /// [`Board::legal_moves`].
+    pub fn standard_status(&self) -> GameStatus {
+        if !self.has_legal_moves() {
+            if self.is_check() {
+                GameStatus::Won
+            } else {
+                GameStatus::Drawn
+            }
+        } else if self.halfmove_clock() >= 100 {
+            GameStatus::Drawn
+        } else {
+            GameStatus::Ongoing
+        }
     }
     pub fn side_to_move(&self) -> Color {
-        self.0.side_to_move().into()
+        self.position.side_to_move().into()
+    }
+    pub fn fullmove_number(&self) -> u16 {
+        self.position.fullmove_number()
+    }
+    pub fn halfmove_clock(&self) -> u8 {
+        self.position.halfmove_clock()
+    }
+    /// Whether the side to move is in check.
+    pub fn is_check(&self) -> bool {
+        !self.position.checkers().is_empty()
+    }
+    pub fn king(&self, color: Color) -> Square {
+        self.position.king(color.into()).into()
+    }
+    pub fn has_legal_moves(&self) -> bool {
+        !self.legal_moves().is_empty()
+    }
+    /// Same position by FIDE's definition, as used for repetitions, with the same pieces in
+    /// hand.
+    pub fn same_position(&self, other: &Board) -> bool {
+        self.position.same_position(&other.position) && self.pockets == other.pockets
+    }
+    /// Whether `color` has enough material left to possibly checkmate, roughly: anything
+    /// beyond a bare king or a king and a single minor piece.
+    pub fn can_checkmate(&self, color: Color) -> bool {
+        use cozy_chess::Piece;
+
+        let pieces = self.position.colors(color.into());
+        let majors_and_pawns = self.position.pieces(Piece::Pawn)
+            | self.position.pieces(Piece::Rook)
+            | self.position.pieces(Piece::Queen);
+        let minors = self.position.pieces(Piece::Knight) | self.position.pieces(Piece::Bishop);
+
+        !(pieces & majors_and_pawns).is_empty() || (pieces & minors).len() > 1
+    }
+    /// What the pieces of `color` are worth, in pawns: 3 for a minor piece, 5 for a rook, 9 for
+    /// a queen.
+    pub fn material(&self, color: Color) -> u32 {
+        use cozy_chess::Piece;
+
+        [
+            (Piece::Pawn, 1),
+            (Piece::Knight, 3),
+            (Piece::Bishop, 3),
+            (Piece::Rook, 5),
+            (Piece::Queen, 9),
+        ]
+        .into_iter()
+        .map(|(piece, value)| self.position.colored_pieces(color.into(), piece).len() * value)
+        .sum()
+    }
+    /// Neither side can ever checkmate: bare kings, a single minor piece, or only bishops that
+    /// all stand on the same square color.
+    pub fn is_insufficient_material(&self) -> bool {
+        use cozy_chess::{BitBoard, Piece};
+
+        let majors_and_pawns = self.position.pieces(Piece::Pawn)
+            | self.position.pieces(Piece::Rook)
+            | self.position.pieces(Piece::Queen);
+        if !majors_and_pawns.is_empty() || self.pockets != [[0; 5]; 2] {
+            return false;
+        }
+
+        let knights = self.position.pieces(Piece::Knight);
+        let bishops = self.position.pieces(Piece::Bishop);
+        match (knights.len(), bishops.len()) {
+            (0, 0) | (1, 0) => true,
+            (0, _) => {
+                bishops.is_subset(BitBoard::DARK_SQUARES)
+                    || bishops.is_subset(BitBoard::LIGHT_SQUARES)
+            }
+            _ => false,
+        }
+    }
+
+    // cozy_chess represents castling as the king capturing its own rook, but a king dragged
+    // to where it ends up castling (or UCI's e1g1) should castle as well, unless that is a
+    // move of its own, as it can be in Chess960
+    fn to_move(&self, from: Square, to: Square, promotion: Option<Piece>) -> cozy_chess::Move {
+        let mv = cozy_chess::Move {
+            from: from.into(),
+            to: to.into(),
+            promotion: promotion.map(Into::into),
+        };
+
+        let color = self.position.side_to_move();
+        let first_rank = cozy_chess::Rank::First.relative_to(color);
+        if mv.from != self.position.king(color)
+            || mv.from.rank() != first_rank
+            || self.position.is_legal(mv)
+        {
+            return mv;
+        }
+
+        let rights = self.position.castle_rights(color);
+        let rook_file = if mv.to == cozy_chess::Square::new(cozy_chess::File::G, first_rank) {
+            rights.short
+        } else if mv.to == cozy_chess::Square::new(cozy_chess::File::C, first_rank) {
+            rights.long
+        } else {
+            None
+        };
+
+        match rook_file {
+            Some(rook_file) => cozy_chess::Move {
+                to: cozy_chess::Square::new(rook_file, first_rank),
+                ..mv
+            },
+            None => mv,
+        }
+    }
+
+    // the position after dropping `piece` on `to`, if the side to move has one to drop and it
+    // can go there: an empty square, not the first or last rank for a pawn, and not leaving the
+    // own king in check, which cozy_chess tells by refusing to build the position
+    fn dropped(&self, piece: Piece, to: Square) -> Option<cozy_chess::Board> {
+        let color = self.side_to_move();
+        let back_rank = matches!(to.rank(), Rank::First | Rank::Eighth);
+        if self.pocket_count(color, piece) == 0
+            || self.piece_on(to).is_some()
+            || (piece == Piece::Pawn && back_rank)
+        {
+            return None;
+        }
+
+        let mut builder = cozy_chess::BoardBuilder::from_board(&self.position);
+        *builder.square_mut(to.into()) = Some((piece.into(), color.into()));
+        builder.side_to_move = color.opposite().into();
+        builder.en_passant = None;
+        builder.halfmove_clock = match piece {
+            Piece::Pawn => 0,
+            _ => builder.halfmove_clock.saturating_add(1),
+        };
+        if color == Color::Black {
+            builder.fullmove_number += 1;
+        }
+        builder.build().ok()
+    }
+
+    // a piece taken by `mv` goes to the mover's pocket, as a pawn if it was one once
+    fn pocket_capture(&mut self, mv: cozy_chess::Move) {
+        let color = self.position.side_to_move();
+        let captured = if self.position.colors(!color).has(mv.to) {
+            if self.promoted.has(mv.to) {
+                Some(cozy_chess::Piece::Pawn)
+            } else {
+                self.position.piece_on(mv.to)
+            }
+        } else if self.is_capture(mv.into()) {
+            // en passant
+            Some(cozy_chess::Piece::Pawn)
+        } else {
+            None
+        };
+
+        if let Some(captured) = captured {
+            let piece = Piece::from(captured);
+            let index = POCKET_PIECES.iter().position(|pocketed| *pocketed == piece);
+            self.pockets[Color::from(color) as usize][index.expect("kings aren't captured")] += 1;
+        }
+    }
+
+    // promoted pieces are followed as they move, and forgotten when taken
+    fn move_promoted(&mut self, mv: cozy_chess::Move) {
+        let was_promoted = self.promoted.has(mv.from);
+        self.promoted &= !mv.from.bitboard();
+        self.promoted &= !mv.to.bitboard();
+        if was_promoted || mv.promotion.is_some() {
+            self.promoted |= mv.to.bitboard();
+        }
+    }
+
+    // the other way around: the king taking its own rook becomes the two-square king move, when
+    // the king starts from the e-file as in standard chess
+    fn to_request(&self, mv: cozy_chess::Move) -> MoveRequest {
+        use cozy_chess::File;
+
+        let color = self.position.side_to_move();
+        let castles =
+            mv.from == self.position.king(color) && self.position.colors(color).has(mv.to);
+        if !castles || mv.from.file() != File::E {
+            return mv.into();
+        }
+
+        let file = if mv.to.file() > mv.from.file() {
+            File::G
+        } else {
+            File::C
+        };
+        cozy_chess::Move {
+            to: cozy_chess::Square::new(file, mv.to.rank()),
+            ..mv
+        }
+        .into()
+    }
+
+    // whether the piece could make the move if its own king's safety didn't matter
+    fn is_pseudo_legal(&self, mv: cozy_chess::Move) -> bool {
+        use cozy_chess::{
+            Piece, Rank, get_bishop_moves, get_king_moves, get_knight_moves, get_pawn_attacks,
+            get_pawn_quiets, get_rook_moves,
+        };
+
+        let board = &self.position;
+        let (Some(piece), Some(color)) = (board.piece_on(mv.from), board.color_on(mv.from)) else {
+            return false;
+        };
+
+        let promotes = piece == Piece::Pawn && mv.to.rank() == Rank::Eighth.relative_to(color);
+        let valid_promotion = match mv.promotion {
+            None => !promotes,
+            Some(Piece::Pawn | Piece::King) => false,
+            Some(_) => promotes,
+        };
+        if !valid_promotion {
+            return false;
+        }
+
+        let blockers = board.occupied();
+        let targets = match piece {
+            Piece::Pawn => {
+                let mut capturable = board.colors(!color);
+                if let Some(file) = board.en_passant() {
+                    capturable |=
+                        cozy_chess::Square::new(file, Rank::Sixth.relative_to(color)).bitboard();
+                }
+                get_pawn_quiets(mv.from, color, blockers)
+                    | (get_pawn_attacks(mv.from, color) & capturable)
+            }
+            Piece::Knight => get_knight_moves(mv.from),
+            Piece::Bishop => get_bishop_moves(mv.from, blockers),
+            Piece::Rook => get_rook_moves(mv.from, blockers),
+            Piece::Queen => get_bishop_moves(mv.from, blockers) | get_rook_moves(mv.from, blockers),
+            Piece::King => get_king_moves(mv.from),
+        };
+
+        (targets & !board.colors(color)).has(mv.to)
     }
 
     // TODO: should this be implemented here? or by the user of the plugin? or at most in a utils mod?
     pub fn needs_promotion(&self, mv: MoveRequest) -> bool {
-        let mv_with_promotion_is_legal = self.is_legal(MoveRequest {
-            from: mv.from,
-            to: mv.to,
+        let MoveRequest::Move {
+            from,
+            to,
+            promotion,
+        } = mv
+        else {
+            return false;
+        };
+        let mv_with_promotion_is_legal = self.is_legal(MoveRequest::Move {
+            from,
+            to,
             promotion: Some(Piece::Queen),
         });
-        let mv_without_promotion_is_legal = self.is_legal(MoveRequest {
-            from: mv.from,
-            to: mv.to,
+        let mv_without_promotion_is_legal = self.is_legal(MoveRequest::Move {
+            from,
+            to,
             promotion: None,
         });
 
-        mv_with_promotion_is_legal && !mv_without_promotion_is_legal && mv.promotion.is_none()
+        mv_with_promotion_is_legal && !mv_without_promotion_is_legal && promotion.is_none()
+    }
+}
+
+fn display_drop(mv: MoveRequest) -> String {
+    let MoveRequest::Drop { piece, to } = mv else {
+        unreachable!("only drops are displayed this way");
+    };
+    let letter = match piece {
+        Piece::Pawn => 'P',
+        Piece::Knight => 'N',
+        Piece::Bishop => 'B',
+        Piece::Rook => 'R',
+        Piece::Queen => 'Q',
+        Piece::King => 'K',
+    };
+    format!("{letter}@{}", cozy_chess::Square::from(to))
+}
+
+// `N@f3`, or `@f3` for a pawn
+fn parse_drop(text: &str) -> Option<MoveRequest> {
+    let (piece, to) = text.split_once('@')?;
+    let piece = match piece {
+        "" | "P" => Piece::Pawn,
+        "N" => Piece::Knight,
+        "B" => Piece::Bishop,
+        "R" => Piece::Rook,
+        "Q" => Piece::Queen,
+        _ => return None,
+    };
+    let to = to.parse::<cozy_chess::Square>().ok()?.into();
+    Some(MoveRequest::Drop { piece, to })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mv(from: Square, to: Square) -> MoveRequest {
+        MoveRequest::Move {
+            from,
+            to,
+            promotion: None,
+        }
+    }
+
+    #[test]
+    fn explains_rejected_moves() {
+        let board = Board::default();
+        assert_eq!(board.validate_move(mv(Square::E2, Square::E4)), Ok(()));
+        assert_eq!(
+            board.validate_move(mv(Square::E4, Square::E5)),
+            Err(RejectionReason::NoPieceOnSource)
+        );
+        assert_eq!(
+            board.validate_move(mv(Square::E7, Square::E5)),
+            Err(RejectionReason::WrongSideToMove)
+        );
+        assert_eq!(
+            board.validate_move(mv(Square::E2, Square::E5)),
+            Err(RejectionReason::IllegalMove)
+        );
+
+        // the e2 pawn is pinned by the rook on e8
+        let pinned = Board::from_fen("4r1k1/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            pinned.validate_move(mv(Square::E2, Square::D3)),
+            Err(RejectionReason::IllegalMove)
+        );
+        let pinned = Board::from_fen("4r1k1/8/8/8/8/3p4/4P3/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            pinned.validate_move(mv(Square::E2, Square::D3)),
+            Err(RejectionReason::LeavesKingInCheck)
+        );
+
+        let promotion = Board::from_fen("6k1/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            promotion.validate_move(mv(Square::E7, Square::E8)),
+            Err(RejectionReason::MissingPromotion)
+        );
+    }
+
+    #[test]
+    fn lists_moves_from_a_square() {
+        let board = Board::from_fen("r3k3/8/8/3pP3/8/8/8/R3K2R w KQq d6 0 1").unwrap();
+
+        let mut king: Vec<_> = board
+            .legal_moves_from(Square::E1)
+            .into_iter()
+            .map(MoveRequest::to)
+            .collect();
+        king.sort_by_key(|square| *square as u8);
+        assert_eq!(
+            king,
+            [
+                Square::C1,
+                Square::D1,
+                Square::F1,
+                Square::G1,
+                Square::D2,
+                Square::E2,
+                Square::F2
+            ]
+        );
+
+        let pawn = board.legal_moves_from(Square::E5);
+        assert_eq!(
+            pawn,
+            [mv(Square::E5, Square::E6), mv(Square::E5, Square::D6)]
+        );
+        assert!(board.is_capture(mv(Square::E5, Square::D6)));
+        assert!(!board.is_capture(mv(Square::E5, Square::E6)));
+        assert!(board.legal_moves_from(Square::A8).is_empty());
+    }
+
+    #[test]
+    fn counts_material() {
+        // white lost a knight and two pawns, a third pawn became the queen on g1
+        let board =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/8/8/8/PPPPP3/RNBQKBQR w KQkq - 0 1").unwrap();
+        assert_eq!(board.material(Color::White), 5 + 3 * 3 + 2 * 5 + 2 * 9);
+        assert_eq!(board.material(Color::Black), 7 + 4 * 3 + 2 * 5 + 9);
+    }
+
+    #[test]
+    fn castles_in_chess960() {
+        assert!(Board::chess960(518) == Some(Board::default()));
+        assert!(Board::chess960(960).is_none());
+
+        let fen = "4k3/8/8/8/8/8/8/R5KR w HA - 0 1";
+        let board = Board::from_fen(fen).unwrap();
+        assert!(board.is_chess960());
+        assert_eq!(board.to_fen(), fen);
+        assert!(!Board::default().is_chess960());
+        assert_eq!(
+            Board::default().to_shredder_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1"
+        );
+
+        // the king is already where short castling puts it, only the rook moves
+        let short = mv(Square::G1, Square::H1);
+        assert!(board.is_legal(short));
+        assert_eq!(board.uci(short), "g1h1");
+        let mut castled = board.clone();
+        castled.play_unchecked(short);
+        assert_eq!(castled.piece_on(Square::F1), Some(Piece::Rook));
+        assert_eq!(castled.piece_on(Square::G1), Some(Piece::King));
+
+        // dragged to c1, the king castles long
+        let mut castled = board.clone();
+        castled.play_unchecked(mv(Square::G1, Square::C1));
+        assert_eq!(castled.piece_on(Square::C1), Some(Piece::King));
+        assert_eq!(castled.piece_on(Square::D1), Some(Piece::Rook));
     }
 }
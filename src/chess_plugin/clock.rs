@@ -0,0 +1,416 @@
+use std::{str::FromStr, time::Duration};
+
+use bevy::prelude::*;
+use derive_more::{Display, Error};
+
+use super::{
+    Board, Color, GameEndReason, GameEnded, GameOutcome, GameResult, LoadGame, LoadPosition,
+    MovePlayed, NewGame,
+};
+
+/// Time given back to a player for each move they make.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Increment {
+    None,
+    /// added after every move
+    Fischer(Duration),
+    /// after every move, gives back the time spent on it, up to this much
+    Bronstein(Duration),
+    /// the clock only starts running after this much of every move
+    SimpleDelay(Duration),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeControlStage {
+    /// moves to be made in this stage, or `None` for the rest of the game
+    pub moves: Option<u32>,
+    pub time: Duration,
+    pub increment: Increment,
+}
+
+/// One or more stages, each adding its time once the previous stage's moves are made. The last
+/// stage repeats if it has a move count, e.g. 40 moves every 2 hours.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeControl {
+    pub stages: Vec<TimeControlStage>,
+}
+
+impl TimeControl {
+    pub fn sudden_death(time: Duration) -> Self {
+        Self::single(time, Increment::None)
+    }
+    pub fn fischer(time: Duration, increment: Duration) -> Self {
+        Self::single(time, Increment::Fischer(increment))
+    }
+    pub fn bronstein(time: Duration, delay: Duration) -> Self {
+        Self::single(time, Increment::Bronstein(delay))
+    }
+    pub fn simple_delay(time: Duration, delay: Duration) -> Self {
+        Self::single(time, Increment::SimpleDelay(delay))
+    }
+
+    fn single(time: Duration, increment: Increment) -> Self {
+        Self {
+            stages: vec![TimeControlStage {
+                moves: None,
+                time,
+                increment,
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Display, Error)]
+#[display("invalid time control: {_0}")]
+pub struct TimeControlParseError(#[error(not(source))] String);
+
+/// Parses the PGN `TimeControl` tag format extended with delays, in seconds: stages separated by
+/// `:`, each `[moves/]time[+increment]`, where the increment may be suffixed with `b` for a
+/// Bronstein delay or `d` for a simple delay. 40/90+30 is `40/5400+30:1800+30`.
+impl FromStr for TimeControl {
+    type Err = TimeControlParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || TimeControlParseError(s.to_string());
+        let seconds = |value: &str| -> Result<Duration, TimeControlParseError> {
+            value
+                .parse::<f32>()
+                .ok()
+                .and_then(|value| Duration::try_from_secs_f32(value).ok())
+                .ok_or_else(error)
+        };
+
+        let stages = s
+            .split(':')
+            .map(|stage| {
+                let (moves, rest) = match stage.split_once('/') {
+                    Some((moves, rest)) => match moves.parse() {
+                        Ok(moves) if moves > 0 => (Some(moves), rest),
+                        _ => return Err(error()),
+                    },
+                    None => (None, stage),
+                };
+                let (time, increment) = match rest.split_once('+') {
+                    Some((time, increment)) => {
+                        let increment = if let Some(delay) = increment.strip_suffix('b') {
+                            Increment::Bronstein(seconds(delay)?)
+                        } else if let Some(delay) = increment.strip_suffix('d') {
+                            Increment::SimpleDelay(seconds(delay)?)
+                        } else {
+                            Increment::Fischer(seconds(increment)?)
+                        };
+                        (time, increment)
+                    }
+                    None => (rest, Increment::None),
+                };
+
+                Ok(TimeControlStage {
+                    moves,
+                    time: seconds(time)?,
+                    increment,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { stages })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SideClock {
+    remaining: Duration,
+    stage: usize,
+    moves_in_stage: u32,
+}
+
+/// The part of a [`ChessClock`] a move changes.
+#[derive(Clone, Copy, Debug)]
+struct ClockState {
+    white: SideClock,
+    black: SideClock,
+    turn_elapsed: Duration,
+    running: bool,
+}
+
+/// Both players' clocks. Ticks for the side to move once the first move is played, and flags
+/// with [`GameEndReason::Timeout`]. Undoing a move winds the clocks back to when it was played,
+/// and redoing it to when it was undone. Only runs when inserted into the app.
+#[derive(Resource, Clone, Debug)]
+pub struct ChessClock {
+    control: TimeControl,
+    white: SideClock,
+    black: SideClock,
+    /// time spent on the current move
+    turn_elapsed: Duration,
+    running: bool,
+    /// the clocks before each move that can be undone, and after each that can be redone
+    before_moves: Vec<ClockState>,
+    after_undone: Vec<ClockState>,
+}
+
+impl ChessClock {
+    pub fn new(control: TimeControl) -> Self {
+        let side = SideClock {
+            remaining: control
+                .stages
+                .first()
+                .map_or(Duration::ZERO, |stage| stage.time),
+            stage: 0,
+            moves_in_stage: 0,
+        };
+
+        Self {
+            control,
+            white: side,
+            black: side,
+            turn_elapsed: Duration::ZERO,
+            running: false,
+            before_moves: Vec::new(),
+            after_undone: Vec::new(),
+        }
+    }
+
+    pub fn control(&self) -> &TimeControl {
+        &self.control
+    }
+    pub fn remaining(&self, color: Color) -> Duration {
+        self.side(color).remaining
+    }
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Moves `color` has left to make before their next time control, if there is one.
+    pub fn moves_to_go(&self, color: Color) -> Option<u32> {
+        let side = self.side(color);
+        let stage = self.control.stages.get(side.stage)?;
+        Some(stage.moves?.saturating_sub(side.moves_in_stage))
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.control.clone());
+    }
+
+    fn side(&self, color: Color) -> &SideClock {
+        match color {
+            Color::White => &self.white,
+            Color::Black => &self.black,
+        }
+    }
+    fn side_mut(&mut self, color: Color) -> &mut SideClock {
+        match color {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        }
+    }
+    pub fn increment(&self, color: Color) -> Increment {
+        let stage = self.side(color).stage;
+        self.control
+            .stages
+            .get(stage)
+            .map_or(Increment::None, |stage| stage.increment)
+    }
+
+    /// Runs `color`'s clock, returning whether it ran out.
+    fn tick(&mut self, color: Color, delta: Duration) -> bool {
+        if !self.running {
+            return false;
+        }
+
+        let consumed = match self.increment(color) {
+            Increment::SimpleDelay(delay) => {
+                (self.turn_elapsed + delta).saturating_sub(delay)
+                    - self.turn_elapsed.saturating_sub(delay)
+            }
+            _ => delta,
+        };
+        self.turn_elapsed += delta;
+
+        let side = self.side_mut(color);
+        side.remaining = side.remaining.saturating_sub(consumed);
+        side.remaining.is_zero()
+    }
+
+    fn state(&self) -> ClockState {
+        ClockState {
+            white: self.white,
+            black: self.black,
+            turn_elapsed: self.turn_elapsed,
+            running: self.running,
+        }
+    }
+    fn restore(&mut self, state: ClockState) {
+        self.white = state.white;
+        self.black = state.black;
+        self.turn_elapsed = state.turn_elapsed;
+        self.running = state.running;
+    }
+
+    /// The last move was undone: back to the moment it was played.
+    pub(super) fn take_back(&mut self) {
+        let Some(before) = self.before_moves.pop() else {
+            // played before the clock was reset
+            return;
+        };
+        self.after_undone.push(self.state());
+        self.restore(before);
+    }
+    /// The last undone move was redone: back to when it was undone.
+    pub(super) fn replay(&mut self) {
+        let Some(after) = self.after_undone.pop() else {
+            return;
+        };
+        self.before_moves.push(self.state());
+        self.restore(after);
+    }
+
+    /// `color` made a move: apply their increment, move on to the next stage if its moves are
+    /// made, and hand the turn over.
+    fn press(&mut self, color: Color) {
+        self.before_moves.push(self.state());
+        // a new move can't be followed by the ones undone before it
+        self.after_undone.clear();
+
+        let bonus = match self.increment(color) {
+            Increment::None | Increment::SimpleDelay(_) => Duration::ZERO,
+            Increment::Fischer(increment) => increment,
+            Increment::Bronstein(delay) => self.turn_elapsed.min(delay),
+        };
+
+        let stages = &self.control.stages;
+        let side = match color {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        };
+        side.remaining += bonus;
+        side.moves_in_stage += 1;
+        if let Some(stage) = stages.get(side.stage) {
+            if stage.moves == Some(side.moves_in_stage) {
+                // the last stage repeats
+                side.stage = (side.stage + 1).min(stages.len() - 1);
+                side.moves_in_stage = 0;
+                side.remaining += stages[side.stage].time;
+            }
+        }
+
+        self.turn_elapsed = Duration::ZERO;
+        self.running = true;
+    }
+}
+
+fn tick_clock(
+    time: Res<Time>,
+    mut clock: ResMut<ChessClock>,
+    board: Res<Board>,
+    result: Res<GameResult>,
+    mut commands: Commands,
+) {
+    if result.is_over() {
+        return;
+    }
+
+    let color = board.side_to_move();
+    if clock.tick(color, time.delta()) {
+        // flagging only loses if the opponent could still checkmate
+        let outcome = if board.can_checkmate(color.opposite()) {
+            GameOutcome::Win(color.opposite())
+        } else {
+            GameOutcome::Draw
+        };
+        commands.trigger(GameEnded {
+            outcome,
+            reason: GameEndReason::Timeout,
+        });
+    }
+}
+
+pub(super) fn setup_clock(app: &mut App) {
+    app.add_systems(Update, tick_clock.run_if(resource_exists::<ChessClock>))
+        .add_observer(
+            |event: Trigger<MovePlayed>, clock: Option<ResMut<ChessClock>>| {
+                if let Some(mut clock) = clock {
+                    clock.press(event.color);
+                }
+            },
+        )
+        .add_observer(|_: Trigger<NewGame>, clock: Option<ResMut<ChessClock>>| {
+            if let Some(mut clock) = clock {
+                clock.reset();
+            }
+        })
+        .add_observer(
+            |_: Trigger<LoadPosition>, clock: Option<ResMut<ChessClock>>| {
+                if let Some(mut clock) = clock {
+                    clock.reset();
+                }
+            },
+        )
+        .add_observer(|_: Trigger<LoadGame>, clock: Option<ResMut<ChessClock>>| {
+            if let Some(mut clock) = clock {
+                clock.reset();
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn parses_multi_stage_controls() {
+        let control: TimeControl = "40/5400+30:1800+30".parse().unwrap();
+        assert_eq!(
+            control.stages,
+            vec![
+                TimeControlStage {
+                    moves: Some(40),
+                    time: 5400 * SECOND,
+                    increment: Increment::Fischer(30 * SECOND),
+                },
+                TimeControlStage {
+                    moves: None,
+                    time: 1800 * SECOND,
+                    increment: Increment::Fischer(30 * SECOND),
+                },
+            ]
+        );
+        assert_eq!(
+            "300+2d".parse::<TimeControl>().unwrap(),
+            TimeControl::simple_delay(300 * SECOND, 2 * SECOND)
+        );
+        assert!("40/".parse::<TimeControl>().is_err());
+        assert!("0/60".parse::<TimeControl>().is_err());
+    }
+
+    #[test]
+    fn applies_increments_and_delays() {
+        let mut fischer = ChessClock::new(TimeControl::fischer(60 * SECOND, 2 * SECOND));
+        fischer.press(Color::White);
+        fischer.tick(Color::Black, 5 * SECOND);
+        fischer.press(Color::Black);
+        assert_eq!(fischer.remaining(Color::Black), 57 * SECOND);
+
+        let mut bronstein = ChessClock::new(TimeControl::bronstein(60 * SECOND, 3 * SECOND));
+        bronstein.press(Color::White);
+        bronstein.tick(Color::Black, 2 * SECOND);
+        bronstein.press(Color::Black);
+        assert_eq!(bronstein.remaining(Color::Black), 60 * SECOND);
+
+        let mut delay = ChessClock::new(TimeControl::simple_delay(60 * SECOND, 3 * SECOND));
+        delay.press(Color::White);
+        delay.tick(Color::Black, 2 * SECOND);
+        delay.tick(Color::Black, 2 * SECOND);
+        assert_eq!(delay.remaining(Color::Black), 59 * SECOND);
+    }
+
+    #[test]
+    fn moves_on_to_the_next_stage() {
+        let mut clock: ChessClock = ChessClock::new("2/60:30".parse().unwrap());
+        clock.press(Color::White);
+        clock.press(Color::White);
+        assert_eq!(clock.remaining(Color::White), 90 * SECOND);
+        assert!(clock.tick(Color::White, 90 * SECOND));
+    }
+}
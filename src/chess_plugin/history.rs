@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+
+use super::{
+    Board, ChessClock, Color, GameResult, MoveRequest, Piece, PieceUpdateQueued, game_end,
+    trigger_piece_changes,
+};
+
+#[derive(Clone)]
+pub struct PlayedMove {
+    pub mv: MoveRequest,
+    /// the position the move was played from
+    pub before: Board,
+}
+
+/// Every move played through [`MoveRequest`] since the last [`super::LoadPosition`],
+/// plus the moves that were undone and can still be redone.
+#[derive(Resource, Default)]
+pub struct MoveHistory {
+    played: Vec<PlayedMove>,
+    undone: Vec<PlayedMove>,
+}
+
+impl MoveHistory {
+    pub fn played(&self) -> &[PlayedMove] {
+        &self.played
+    }
+
+    /// The pieces of `color` taken by the moves played, by type. Only captures made since the
+    /// game was set up count, so a game loaded from the middle starts with none.
+    pub fn captured(&self, color: Color) -> [(Piece, u8); 5] {
+        let mut captured = [
+            Piece::Pawn,
+            Piece::Knight,
+            Piece::Bishop,
+            Piece::Rook,
+            Piece::Queen,
+        ]
+        .map(|piece| (piece, 0));
+
+        for PlayedMove { mv, before } in &self.played {
+            if before.side_to_move() == color || !before.is_capture(*mv) {
+                continue;
+            }
+            // en passant lands on an empty square
+            let taken = before.piece_on(mv.to()).unwrap_or(Piece::Pawn);
+            if let Some((_, count)) = captured.iter_mut().find(|(piece, _)| *piece == taken) {
+                *count += 1;
+            }
+        }
+        captured
+    }
+
+    pub(super) fn push(&mut self, mv: MoveRequest, before: Board) {
+        self.played.push(PlayedMove { mv, before });
+        // a new move invalidates whatever was undone before it
+        self.undone.clear();
+    }
+    pub(super) fn undo(&mut self) -> Option<&PlayedMove> {
+        let played = self.played.pop()?;
+        self.undone.push(played);
+        self.undone.last()
+    }
+    pub(super) fn redo(&mut self) -> Option<&PlayedMove> {
+        let undone = self.undone.pop()?;
+        self.played.push(undone);
+        self.played.last()
+    }
+    pub(super) fn clear(&mut self) {
+        self.played.clear();
+        self.undone.clear();
+    }
+}
+
+/// Rewinds the [`Board`] to the position before the last played move.
+#[derive(Event, Clone, Copy)]
+pub struct UndoMove;
+
+/// Replays the last move that was rewound by [`UndoMove`].
+#[derive(Event, Clone, Copy)]
+pub struct RedoMove;
+
+pub(super) fn setup_history(app: &mut App) {
+    app.init_resource::<MoveHistory>()
+        .add_observer(
+            |_: Trigger<UndoMove>,
+             mut history: ResMut<MoveHistory>,
+             mut board: ResMut<Board>,
+             mut result: ResMut<GameResult>,
+             clock: Option<ResMut<ChessClock>>,
+             mut commands: Commands| {
+                let Some(played) = history.undo() else {
+                    // nothing to undo
+                    return;
+                };
+                *board = played.before.clone();
+                // taking back the last move reopens a finished game
+                result.clear();
+                if let Some(mut clock) = clock {
+                    clock.take_back();
+                }
+
+                commands.trigger(PieceUpdateQueued);
+            },
+        )
+        .add_observer(
+            |_: Trigger<RedoMove>,
+             mut history: ResMut<MoveHistory>,
+             mut board: ResMut<Board>,
+             result: Res<GameResult>,
+             clock: Option<ResMut<ChessClock>>,
+             mut commands: Commands| {
+                if result.is_over() {
+                    return;
+                }
+                let Some(played) = history.redo() else {
+                    // nothing to redo
+                    return;
+                };
+                *board = played.before.clone();
+                board.play_unchecked(played.mv);
+                if let Some(mut clock) = clock {
+                    clock.replay();
+                }
+                trigger_piece_changes(&played.before, &board, &mut commands);
+
+                if let Some(ended) = game_end(&board, &history) {
+                    commands.trigger(ended);
+                }
+
+                commands.trigger(PieceUpdateQueued);
+            },
+        );
+}
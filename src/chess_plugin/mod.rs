@@ -1,26 +1,131 @@
+use std::sync::Arc;
+
 use bevy::prelude::*;
 use num_enum::IntoPrimitive;
 
+mod ai;
+pub use ai::*;
 mod board;
 pub use board::*;
+mod clock;
+pub use clock::*;
+mod history;
+pub use history::*;
+mod move_entry;
+pub use move_entry::*;
+mod outcome;
+pub use outcome::*;
+mod pgn;
+pub use pgn::*;
+mod sync;
+pub use sync::*;
+mod uci;
+pub use uci::*;
+mod variant;
+pub use variant::*;
 
-pub struct ChessPlugin;
+/// Hosts a game of chess, played by the rules of `variant`.
+pub struct ChessPlugin {
+    pub variant: Arc<dyn ChessVariant>,
+}
 
-#[derive(Event, Clone, Copy)]
-pub struct MoveRequest {
-    pub from: Square,
-    pub to: Square,
-    pub promotion: Option<Piece>,
+impl Default for ChessPlugin {
+    fn default() -> Self {
+        Self {
+            variant: Arc::new(Standard),
+        }
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveRequest {
+    /// a piece on the board going from one square to another
+    Move {
+        from: Square,
+        to: Square,
+        promotion: Option<Piece>,
+    },
+    /// a piece from the mover's pocket put on an empty square, in variants that have pockets
+    /// like [`Crazyhouse`]
+    Drop { piece: Piece, to: Square },
+}
+
+impl MoveRequest {
+    /// The square the piece ends up on.
+    pub fn to(self) -> Square {
+        match self {
+            MoveRequest::Move { to, .. } | MoveRequest::Drop { to, .. } => to,
+        }
+    }
+
+    /// The square the piece leaves, none for a drop.
+    pub fn source(self) -> Option<Square> {
+        match self {
+            MoveRequest::Move { from, .. } => Some(from),
+            MoveRequest::Drop { .. } => None,
+        }
+    }
+
+    pub fn promotion(self) -> Option<Piece> {
+        match self {
+            MoveRequest::Move { promotion, .. } => promotion,
+            MoveRequest::Drop { .. } => None,
+        }
+    }
+}
+
+/// Emitted after a [`MoveRequest`] was played on the [`Board`].
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MovePlayed {
+    pub mv: MoveRequest,
+    /// the side that played it
+    pub color: Color,
+}
+
+/// Emitted instead of playing a [`MoveRequest`] that can't be played.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveRejected {
+    pub request: MoveRequest,
+    pub reason: RejectionReason,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RejectionReason {
+    WrongSideToMove,
+    /// nothing to move on the source square, or to drop in the pocket
+    NoPieceOnSource,
+    LeavesKingInCheck,
+    /// a pawn reaching the last rank needs a promotion set
+    MissingPromotion,
+    GameOver,
+    /// the piece doesn't move like that
+    IllegalMove,
+}
+
+/// Replaces the current [`Board`] and refreshes every [`Square`] entity to match it.
+#[derive(Event, Clone)]
+pub struct LoadPosition(pub Board);
+
 #[derive(Event, Clone, Copy)]
 struct PieceUpdateQueued;
 
 impl Plugin for ChessPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Board>();
+        app.insert_resource(
+            self.variant
+                .start_position()
+                .with_variant(self.variant.clone()),
+        );
 
         setup_move(app);
+        setup_sync(app);
+        setup_load_position(app);
+        setup_history(app);
+        setup_pgn(app);
+        setup_outcome(app);
+        setup_clock(app);
+        setup_ai(app);
+        setup_uci(app);
         app.add_systems(PostStartup, |mut commands: Commands| {
             commands.trigger(PieceUpdateQueued);
         });
@@ -33,9 +138,18 @@ pub enum Color {
     Black,
 }
 
-impl From<MoveRequest> for cozy_chess::Move {
-    fn from(value: MoveRequest) -> Self {
-        cozy_chess::Move {
+impl Color {
+    pub fn opposite(self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+impl From<cozy_chess::Move> for MoveRequest {
+    fn from(value: cozy_chess::Move) -> Self {
+        MoveRequest::Move {
             from: value.from.into(),
             to: value.to.into(),
             promotion: value.promotion.map(Into::into),
@@ -63,56 +177,50 @@ impl From<cozy_chess::Color> for Color {
         }
     }
 }
+impl From<Color> for cozy_chess::Color {
+    fn from(value: Color) -> Self {
+        match value {
+            Color::White => cozy_chess::Color::White,
+            Color::Black => cozy_chess::Color::Black,
+        }
+    }
+}
 
 fn setup_move(app: &mut App) {
     app.add_observer(
-        |_: Trigger<PieceUpdateQueued>,
-         mut commands: Commands,
-         board: Res<Board>,
-         squares: Query<(&Square, Entity)>| {
-            for (square, entity) in squares.iter() {
-                let piece_on = board.piece_on(*square);
-                let color_on = board.color_on(*square);
-
-                match (piece_on, color_on) {
-                    (Some(piece), Some(color)) => {
-                        commands.entity(entity).remove::<ColoredPiece>();
-                        commands
-                            .entity(entity)
-                            .insert(ColoredPiece { piece, color });
-                    }
-                    (None, None) => {
-                        commands.entity(entity).remove::<ColoredPiece>();
-                    }
-                    _ => {
-                        return Err("piece and color mismatch".into());
-                    }
-                }
-            }
-            Ok(())
-        },
-    )
-    .add_observer(
-        |event: Trigger<MoveRequest>, mut board: ResMut<Board>, mut commands: Commands| {
+        |event: Trigger<MoveRequest>,
+         mut board: ResMut<Board>,
+         mut history: ResMut<MoveHistory>,
+         result: Res<GameResult>,
+         mut commands: Commands| {
             let mv = *event;
 
-            if !board.is_legal(mv) {
-                // ignore illegal moves
+            let validation = if result.is_over() {
+                Err(RejectionReason::GameOver)
+            } else {
+                board.validate_move(mv)
+            };
+            if let Err(reason) = validation {
+                commands.trigger(MoveRejected {
+                    request: mv,
+                    reason,
+                });
                 return Ok(());
             }
+            // castling reaches the history and observers the same way whoever asked for it
+            let mv = board.canonical(mv);
+            let before = board.clone();
             board.play_unchecked(mv);
 
-            // TODO
-            match board.status() {
-                GameStatus::Ongoing => {
-                    // continue the game
-                }
-                GameStatus::Drawn => {
-                    eprintln!("Drawn!");
-                }
-                GameStatus::Won => {
-                    eprintln!("Checkmate!");
-                }
+            commands.trigger(MovePlayed {
+                mv,
+                color: board.side_to_move().opposite(),
+            });
+            trigger_piece_changes(&before, &board, &mut commands);
+            history.push(mv, before);
+
+            if let Some(ended) = game_end(&board, &history) {
+                commands.trigger(ended);
             }
 
             commands.trigger(PieceUpdateQueued);
@@ -122,6 +230,27 @@ fn setup_move(app: &mut App) {
     );
 }
 
+fn setup_load_position(app: &mut App) {
+    app.add_observer(
+        |event: Trigger<LoadPosition>,
+         mut board: ResMut<Board>,
+         mut history: ResMut<MoveHistory>,
+         mut result: ResMut<GameResult>,
+         mut commands: Commands| {
+            // the plugin's variant goes on with the new position
+            *board = event.0.clone().with_variant(board.variant().clone());
+            history.clear();
+            result.clear();
+
+            if let Some(ended) = game_end(&board, &history) {
+                commands.trigger(ended);
+            }
+
+            commands.trigger(PieceUpdateQueued);
+        },
+    );
+}
+
 pub enum GameStatus {
     Ongoing,
     Drawn,
@@ -137,7 +266,7 @@ impl From<cozy_chess::GameStatus> for GameStatus {
     }
 }
 
-#[derive(Component, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Square {
     A1,
     B1,
@@ -211,6 +340,13 @@ impl From<&Square> for cozy_chess::Square {
     }
 }
 
+impl From<cozy_chess::Square> for Square {
+    fn from(value: cozy_chess::Square) -> Self {
+        // both enumerations go A1, B1, ..., H8
+        ALL_SQUARES[value as usize]
+    }
+}
+
 impl From<&Piece> for cozy_chess::Piece {
     fn from(value: &Piece) -> Self {
         (*value).into()
@@ -535,7 +671,7 @@ pub const ALL_SQUARES: [Square; 64] = [
     Square::H8,
 ];
 
-#[derive(Component, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
 #[require(
     Square = explicit::<Piece, Square>()
 )]
@@ -548,7 +684,7 @@ pub enum Piece {
     King,
 }
 
-#[derive(Component, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ColoredPiece {
     pub piece: Piece,
     pub color: Color,
@@ -0,0 +1,168 @@
+use derive_more::{Display, Error};
+
+use super::{Board, MoveRequest};
+
+/// Why typed text doesn't name a single legal move.
+#[derive(Clone, Debug, Display, Error, PartialEq, Eq)]
+pub enum MoveEntryError {
+    #[display("no legal move matches \"{_0}\"")]
+    NoMatch(#[error(not(source))] String),
+    /// `candidates` in SAN
+    #[display("\"{input}\" could be {}", candidates.join(", "))]
+    Ambiguous {
+        input: String,
+        candidates: Vec<String>,
+    },
+}
+
+/// A legal move, with its SAN and every way of typing it in full.
+struct Entry {
+    mv: MoveRequest,
+    san: String,
+    spellings: Vec<String>,
+}
+
+impl Board {
+    /// Reads a move typed in SAN, like `Nf3` or `exd8=Q`, or in UCI, like `e2e4`. Check marks and
+    /// the `=` of a promotion may be left out, and so may the end of the move, as long as only
+    /// one legal move starts that way.
+    pub fn parse_move_entry(&self, input: &str) -> Result<MoveRequest, MoveEntryError> {
+        let input = normalize(input);
+        let entries = self.entries();
+
+        // a piece and its destination without the file or rank telling which piece, say
+        // "Nd2", spells more than one move
+        let mut candidates: Vec<_> = entries
+            .iter()
+            .filter(|entry| entry.spellings.iter().any(|spelling| *spelling == input))
+            .collect();
+        if candidates.is_empty() && !input.is_empty() {
+            candidates = entries
+                .iter()
+                .filter(|entry| {
+                    entry
+                        .spellings
+                        .iter()
+                        .any(|spelling| spelling.starts_with(&input))
+                })
+                .collect();
+        }
+
+        match candidates[..] {
+            [] => Err(MoveEntryError::NoMatch(input)),
+            [entry] => Ok(entry.mv),
+            _ => Err(MoveEntryError::Ambiguous {
+                candidates: candidates.iter().map(|entry| entry.san.clone()).collect(),
+                input,
+            }),
+        }
+    }
+
+    /// The legal moves, in SAN, that `input` is the beginning of.
+    pub fn complete_move_entry(&self, input: &str) -> Vec<String> {
+        let input = normalize(input);
+        if input.is_empty() {
+            return Vec::new();
+        }
+
+        self.entries()
+            .into_iter()
+            .filter(|entry| {
+                entry
+                    .spellings
+                    .iter()
+                    .any(|spelling| spelling.starts_with(&input))
+            })
+            .map(|entry| entry.san)
+            .collect()
+    }
+
+    fn entries(&self) -> Vec<Entry> {
+        self.legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let san = self.san(mv).trim_end_matches(['+', '#']).to_string();
+                let uci = self.uci(mv);
+                let mut spellings = vec![san.clone(), san.replace('=', "")];
+                let drop = matches!(mv, MoveRequest::Drop { .. });
+                if san.starts_with(['N', 'B', 'R', 'Q', 'K']) && !drop {
+                    let capture = if self.is_capture(mv) { "x" } else { "" };
+                    spellings.push(format!("{}{capture}{}", &san[..1], &uci[2..4]));
+                }
+                spellings.push(uci);
+
+                Entry { mv, san, spellings }
+            })
+            .collect()
+    }
+}
+
+fn normalize(input: &str) -> String {
+    let input = input.trim().trim_end_matches(['+', '#', '!', '?']);
+    // castling is often typed with zeros
+    if input.starts_with("0-0") {
+        input.replace('0', "O")
+    } else {
+        input.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess_plugin::{Piece, Square};
+
+    fn mv(from: Square, to: Square) -> MoveRequest {
+        MoveRequest::Move {
+            from,
+            to,
+            promotion: None,
+        }
+    }
+
+    #[test]
+    fn reads_typed_moves() {
+        let board = Board::default();
+        let e4 = mv(Square::E2, Square::E4);
+        assert_eq!(board.parse_move_entry("e4"), Ok(e4));
+        assert_eq!(board.parse_move_entry("e2e4"), Ok(e4));
+        assert_eq!(
+            board.parse_move_entry(" Nf3 "),
+            Ok(mv(Square::G1, Square::F3))
+        );
+        // only one knight move goes to the f-file
+        assert_eq!(board.parse_move_entry("Nf"), Ok(mv(Square::G1, Square::F3)));
+        assert_eq!(board.complete_move_entry("N"), ["Na3", "Nc3", "Nf3", "Nh3"]);
+        assert_eq!(
+            board.parse_move_entry("e5"),
+            Err(MoveEntryError::NoMatch("e5".to_string()))
+        );
+
+        let knights = Board::from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+        assert_eq!(
+            knights.parse_move_entry("Nd2"),
+            Err(MoveEntryError::Ambiguous {
+                input: "Nd2".to_string(),
+                candidates: vec!["Nbd2".to_string(), "Nfd2".to_string()],
+            })
+        );
+        assert_eq!(
+            knights.parse_move_entry("Nbd2"),
+            Ok(mv(Square::B1, Square::D2))
+        );
+
+        let promotion = Board::from_fen("3r2k1/4P3/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let queen = MoveRequest::Move {
+            from: Square::E7,
+            to: Square::D8,
+            promotion: Some(Piece::Queen),
+        };
+        assert_eq!(promotion.parse_move_entry("exd8=Q+"), Ok(queen));
+        assert_eq!(promotion.parse_move_entry("exd8Q"), Ok(queen));
+        assert_eq!(promotion.parse_move_entry("e7d8q"), Ok(queen));
+        assert_eq!(
+            promotion.parse_move_entry("0-0"),
+            Ok(mv(Square::E1, Square::G1))
+        );
+    }
+}
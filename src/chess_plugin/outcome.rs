@@ -0,0 +1,187 @@
+use std::{
+    hash::{BuildHasher, RandomState},
+    sync::Arc,
+};
+
+use bevy::prelude::*;
+
+use super::{Board, ChessVariant, Color, GameStatus, MoveHistory, PieceUpdateQueued};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameOutcome {
+    Win(Color),
+    Draw,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEndReason {
+    Checkmate,
+    Stalemate,
+    InsufficientMaterial,
+    FiftyMoveRule,
+    ThreefoldRepetition,
+    Resignation,
+    Timeout,
+    Agreement,
+    /// the goal of the [`super::ChessVariant`] was reached, like a king on the hill
+    VariantGoal,
+}
+
+/// Emitted once when the current game ends. No [`super::MoveRequest`] is played after it until
+/// a new game starts, through [`NewGame`], [`super::LoadPosition`] or [`super::LoadGame`].
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameEnded {
+    pub outcome: GameOutcome,
+    pub reason: GameEndReason,
+}
+
+/// How the current game ended, if it did.
+#[derive(Resource, Default, Deref)]
+pub struct GameResult(Option<GameEnded>);
+
+impl GameResult {
+    pub fn is_over(&self) -> bool {
+        self.0.is_some()
+    }
+    pub(super) fn clear(&mut self) {
+        self.0 = None;
+    }
+}
+
+/// The given side resigns the current game.
+#[derive(Event, Clone, Copy)]
+pub struct Resign(pub Color);
+
+/// Both sides agree to a draw.
+#[derive(Event, Clone, Copy)]
+pub struct AgreeDraw;
+
+/// Starts over from the initial position, as [`StartingPosition`] picks it.
+#[derive(Event, Clone, Copy)]
+pub struct NewGame;
+
+/// Where games start from: the first one, when it isn't [`StartingPosition::Standard`], and
+/// every [`NewGame`].
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StartingPosition {
+    #[default]
+    Standard,
+    /// the Chess960 position with this number, from 0 to 959
+    Chess960(u32),
+    /// a different Chess960 position each game
+    RandomChess960,
+}
+
+impl StartingPosition {
+    /// The position to start from, played by `variant`.
+    pub fn board(self, variant: &Arc<dyn ChessVariant>) -> Board {
+        let board = match self {
+            StartingPosition::Standard => variant.start_position(),
+            StartingPosition::Chess960(index) => {
+                Board::chess960(index).expect("Chess960 positions are numbered 0 to 959")
+            }
+            StartingPosition::RandomChess960 => {
+                // no need for more randomness than the standard library's hasher seeds
+                let index = RandomState::new().hash_one(()) % 960;
+                Board::chess960(index as u32).expect("within 0 to 959")
+            }
+        };
+        board.with_variant(variant.clone())
+    }
+}
+
+/// Whether the game is over on the board itself, i.e. without a resignation, agreement or flag.
+pub(super) fn game_end(board: &Board, history: &MoveHistory) -> Option<GameEnded> {
+    let (outcome, reason) = match board.status() {
+        GameStatus::Won if board.has_legal_moves() => (
+            GameOutcome::Win(board.side_to_move().opposite()),
+            GameEndReason::VariantGoal,
+        ),
+        GameStatus::Won => (
+            GameOutcome::Win(board.side_to_move().opposite()),
+            GameEndReason::Checkmate,
+        ),
+        GameStatus::Drawn if board.has_legal_moves() => {
+            (GameOutcome::Draw, GameEndReason::FiftyMoveRule)
+        }
+        GameStatus::Drawn => (GameOutcome::Draw, GameEndReason::Stalemate),
+        GameStatus::Ongoing if board.is_insufficient_material() => {
+            (GameOutcome::Draw, GameEndReason::InsufficientMaterial)
+        }
+        GameStatus::Ongoing if is_threefold_repetition(board, history) => {
+            (GameOutcome::Draw, GameEndReason::ThreefoldRepetition)
+        }
+        GameStatus::Ongoing => return None,
+    };
+
+    Some(GameEnded { outcome, reason })
+}
+
+fn is_threefold_repetition(board: &Board, history: &MoveHistory) -> bool {
+    // a capture or pawn move resets the halfmove clock, and no earlier position can repeat
+    let reversible = usize::from(board.halfmove_clock());
+    let repetitions = history
+        .played()
+        .iter()
+        .rev()
+        .take(reversible)
+        .filter(|played| played.before.same_position(board))
+        .count();
+
+    // the current position is the third occurrence
+    repetitions >= 2
+}
+
+pub(super) fn setup_outcome(app: &mut App) {
+    app.init_resource::<GameResult>()
+        .init_resource::<StartingPosition>()
+        .add_systems(
+            Startup,
+            |starting: Res<StartingPosition>, mut board: ResMut<Board>| {
+                if *starting != StartingPosition::Standard {
+                    *board = starting.board(board.variant());
+                }
+            },
+        )
+        .add_observer(
+            |event: Trigger<GameEnded>, mut result: ResMut<GameResult>| {
+                result.0 = Some(*event);
+            },
+        )
+        .add_observer(
+            |event: Trigger<Resign>, result: Res<GameResult>, mut commands: Commands| {
+                if result.is_over() {
+                    return;
+                }
+                commands.trigger(GameEnded {
+                    outcome: GameOutcome::Win(event.0.opposite()),
+                    reason: GameEndReason::Resignation,
+                });
+            },
+        )
+        .add_observer(
+            |_: Trigger<AgreeDraw>, result: Res<GameResult>, mut commands: Commands| {
+                if result.is_over() {
+                    return;
+                }
+                commands.trigger(GameEnded {
+                    outcome: GameOutcome::Draw,
+                    reason: GameEndReason::Agreement,
+                });
+            },
+        )
+        .add_observer(
+            |_: Trigger<NewGame>,
+             starting: Res<StartingPosition>,
+             mut board: ResMut<Board>,
+             mut history: ResMut<MoveHistory>,
+             mut result: ResMut<GameResult>,
+             mut commands: Commands| {
+                *board = starting.board(board.variant());
+                history.clear();
+                result.clear();
+
+                commands.trigger(PieceUpdateQueued);
+            },
+        );
+}
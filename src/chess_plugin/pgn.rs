@@ -0,0 +1,377 @@
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use derive_more::{Display, Error};
+
+use super::{
+    Board, Color, GameOutcome, GameResult, MoveHistory, MoveRequest, PieceUpdateQueued, game_end,
+};
+
+/// A single game in Portable Game Notation.
+#[derive(Clone)]
+pub struct Pgn {
+    /// tag pairs, in the order they are written
+    pub tags: Vec<(String, String)>,
+    pub start: Board,
+    pub moves: Vec<MoveRequest>,
+}
+
+#[derive(Debug, Display, Error)]
+pub enum PgnError {
+    #[display("malformed tag pair: {_0}")]
+    MalformedTag(#[error(not(source))] String),
+    #[display("invalid FEN tag")]
+    InvalidFen(cozy_chess::FenParseError),
+    #[display("unterminated comment or variation")]
+    Unterminated,
+    #[display("illegal move {san} at ply {ply}")]
+    IllegalMove { ply: usize, san: String },
+}
+
+/// Replaces the current game with a [`Pgn`], replaying its moves into the [`Board`] and the
+/// [`MoveHistory`].
+#[derive(Event, Clone)]
+pub struct LoadGame(pub Pgn);
+
+impl Pgn {
+    /// Exports the moves played since the last [`super::LoadPosition`], with the Seven Tag
+    /// Roster filled in as far as the game itself knows it.
+    pub fn from_history(history: &MoveHistory, board: &Board, result: &GameResult) -> Self {
+        let start = history
+            .played()
+            .first()
+            .map_or_else(|| board.clone(), |played| played.before.clone());
+
+        let mut tags = vec![
+            ("Event".to_string(), "?".to_string()),
+            ("Site".to_string(), "?".to_string()),
+            ("Date".to_string(), today()),
+            ("Round".to_string(), "?".to_string()),
+            ("White".to_string(), "?".to_string()),
+            ("Black".to_string(), "?".to_string()),
+            ("Result".to_string(), result_tag(result).to_string()),
+        ];
+        let variant = start.variant();
+        if !variant.standard_rules() {
+            tags.push(("Variant".to_string(), variant.name().to_string()));
+        } else if start.is_chess960() {
+            tags.push(("Variant".to_string(), "Chess960".to_string()));
+        }
+        if start != Board::default() {
+            tags.push(("SetUp".to_string(), "1".to_string()));
+            tags.push(("FEN".to_string(), start.to_fen()));
+        }
+
+        Self {
+            tags,
+            start,
+            moves: history.played().iter().map(|played| played.mv).collect(),
+        }
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn result_tag(result: &GameResult) -> &'static str {
+    match result.map(|ended| ended.outcome) {
+        None => "*",
+        Some(GameOutcome::Draw) => "1/2-1/2",
+        Some(GameOutcome::Win(Color::White)) => "1-0",
+        Some(GameOutcome::Win(Color::Black)) => "0-1",
+    }
+}
+
+// PGN dates are YYYY.MM.DD, in UTC here
+fn today() -> String {
+    let Ok(since_epoch) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+        return "????.??.??".to_string();
+    };
+
+    // civil date from days since the epoch, see https://howardhinnant.github.io/date_algorithms.html
+    let z = since_epoch.as_secs() / 86_400 + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!("{year:04}.{month:02}.{day:02}")
+}
+
+impl fmt::Display for Pgn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{name} \"{value}\"]")?;
+        }
+        writeln!(f)?;
+
+        let mut tokens = Vec::with_capacity(self.moves.len() * 3 / 2 + 1);
+        let mut board = self.start.clone();
+        for (ply, mv) in self.moves.iter().enumerate() {
+            let number = board.fullmove_number();
+            match board.side_to_move() {
+                Color::White => tokens.push(format!("{number}.")),
+                Color::Black if ply == 0 => tokens.push(format!("{number}...")),
+                Color::Black => {}
+            }
+            tokens.push(board.san(*mv));
+            board.play_unchecked(*mv);
+        }
+        tokens.push(self.tag("Result").unwrap_or("*").to_string());
+
+        // export format keeps movetext lines under 80 characters
+        let mut line_len = 0;
+        for token in tokens {
+            if line_len > 0 && line_len + 1 + token.len() > 79 {
+                writeln!(f)?;
+                line_len = 0;
+            } else if line_len > 0 {
+                write!(f, " ")?;
+                line_len += 1;
+            }
+            write!(f, "{token}")?;
+            line_len += token.len();
+        }
+        writeln!(f)
+    }
+}
+
+impl FromStr for Pgn {
+    type Err = PgnError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tags = Vec::new();
+        let mut movetext = String::new();
+        for line in s.lines() {
+            let line = line.trim();
+            if line.starts_with('%') {
+                // escape mechanism, the line is ignored
+                continue;
+            }
+            if line.starts_with('[') && movetext.trim().is_empty() {
+                tags.push(parse_tag(line).ok_or_else(|| PgnError::MalformedTag(line.into()))?);
+            } else {
+                movetext.push_str(line);
+                movetext.push('\n');
+            }
+        }
+
+        let start = match tags.iter().find(|(name, _)| name == "FEN") {
+            Some((_, fen)) => Board::from_fen(fen).map_err(PgnError::InvalidFen)?,
+            None => Board::default(),
+        };
+
+        let mut board = start.clone();
+        let mut moves = Vec::new();
+        for token in movetext_tokens(&movetext)? {
+            let Some(san) = san_token(token) else {
+                continue;
+            };
+            let mv = board.parse_san(&san).map_err(|_| PgnError::IllegalMove {
+                ply: moves.len() + 1,
+                san: san.clone(),
+            })?;
+            board.play_unchecked(mv);
+            moves.push(mv);
+        }
+
+        Ok(Self { tags, start, moves })
+    }
+}
+
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unescaped.push(chars.next()?);
+        } else {
+            unescaped.push(c);
+        }
+    }
+
+    Some((name.to_string(), unescaped))
+}
+
+// splits movetext into tokens, dropping comments and (possibly nested) variations
+fn movetext_tokens(movetext: &str) -> Result<Vec<&str>, PgnError> {
+    let mut tokens = Vec::new();
+    let mut variation_depth = 0usize;
+    let mut rest = movetext;
+
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '{' => {
+                let end = rest.find('}').ok_or(PgnError::Unterminated)?;
+                rest = &rest[end + 1..];
+            }
+            ';' => {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                rest = &rest[end..];
+            }
+            '(' => {
+                variation_depth += 1;
+                rest = &rest[1..];
+            }
+            ')' => {
+                variation_depth = variation_depth
+                    .checked_sub(1)
+                    .ok_or(PgnError::Unterminated)?;
+                rest = &rest[1..];
+            }
+            c if c.is_whitespace() => {
+                rest = &rest[c.len_utf8()..];
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || "{;()".contains(c))
+                    .unwrap_or(rest.len());
+                if variation_depth == 0 {
+                    tokens.push(&rest[..end]);
+                }
+                rest = &rest[end..];
+            }
+        }
+    }
+
+    if variation_depth != 0 {
+        return Err(PgnError::Unterminated);
+    }
+    Ok(tokens)
+}
+
+// the SAN part of a movetext token, if it has one
+fn san_token(token: &str) -> Option<String> {
+    if matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*") || token.starts_with('$') {
+        return None;
+    }
+
+    // move numbers may be glued to the move, as in "12.e4" or "12...e5"
+    let san = match token.rfind('.') {
+        Some(dot) => &token[dot + 1..],
+        None => token,
+    };
+    let san = san.trim_end_matches(['!', '?']);
+    if san.is_empty() {
+        return None;
+    }
+
+    // some tools write castling with zeros
+    let (san, check) = san.split_at(san.find(['+', '#']).unwrap_or(san.len()));
+    let san = match san {
+        "0-0-0" => "O-O-O",
+        "0-0" => "O-O",
+        san => san,
+    };
+    Some(format!("{san}{check}"))
+}
+
+pub(super) fn setup_pgn(app: &mut App) {
+    app.add_observer(
+        |event: Trigger<LoadGame>,
+         mut board: ResMut<Board>,
+         mut history: ResMut<MoveHistory>,
+         mut result: ResMut<GameResult>,
+         mut commands: Commands| {
+            let pgn = &event.0;
+
+            let mut replayed_board = pgn.start.clone().with_variant(board.variant().clone());
+            let mut replayed_history = MoveHistory::default();
+            for mv in &pgn.moves {
+                if !replayed_board.is_legal(*mv) {
+                    return Err("game contains an illegal move".into());
+                }
+                let mv = replayed_board.canonical(*mv);
+                let before = replayed_board.clone();
+                replayed_board.play_unchecked(mv);
+                replayed_history.push(mv, before);
+            }
+
+            *board = replayed_board;
+            *history = replayed_history;
+            result.clear();
+
+            if let Some(ended) = game_end(&board, &history) {
+                commands.trigger(ended);
+            }
+
+            commands.trigger(PieceUpdateQueued);
+
+            Ok(())
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_text() {
+        let text = r#"[Event "Casual \"blitz\""]
+[Result "*"]
+
+1. e4 {best by test} e5 2. Nf3 (2. f4 exf4) Nc6 3. Bc4 Bc5 4. O-O Nf6 $1 *
+"#;
+        let pgn: Pgn = text.parse().unwrap();
+        assert_eq!(pgn.tag("Event"), Some("Casual \"blitz\""));
+        assert_eq!(pgn.moves.len(), 8);
+
+        let exported = pgn.to_string();
+        assert!(exported.contains("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. O-O Nf6 *"));
+
+        let reparsed: Pgn = exported.parse().unwrap();
+        assert_eq!(reparsed.moves.len(), pgn.moves.len());
+    }
+
+    #[test]
+    fn starts_from_fen_tag_with_black_to_move() {
+        let text = r#"[SetUp "1"]
+[FEN "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"]
+
+1... c5 2. Nf3 *"#;
+        let pgn: Pgn = text.parse().unwrap();
+        assert_eq!(pgn.moves.len(), 2);
+        assert!(pgn.to_string().contains("1... c5 2. Nf3 *"));
+    }
+
+    #[test]
+    fn reads_castling_written_with_zeros() {
+        let text = r#"[SetUp "1"]
+[FEN "r3k3/8/8/8/8/8/8/R3K2R w KQq - 0 1"]
+
+1. 0-0 0-0-0 *"#;
+        let pgn: Pgn = text.parse().unwrap();
+        assert!(pgn.to_string().contains("1. O-O O-O-O *"));
+
+        let text = r#"[SetUp "1"]
+[FEN "3k4/8/8/8/8/8/8/R3K3 w Q - 0 1"]
+
+1. 0-0-0+ *"#;
+        let pgn: Pgn = text.parse().unwrap();
+        assert!(pgn.to_string().contains("1. O-O-O+ *"));
+    }
+
+    #[test]
+    fn rejects_illegal_moves() {
+        let err = "1. e4 e5 2. Ke3 *".parse::<Pgn>().err().unwrap();
+        assert!(matches!(err, PgnError::IllegalMove { ply: 3, .. }));
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+
+use super::{ALL_SQUARES, Board, Color, ColoredPiece, Piece, PieceUpdateQueued, Square};
+
+/// A piece went from one square to another, as part of a played move. Castling moves the king
+/// and the rook.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PieceMoved {
+    pub from: Square,
+    pub to: Square,
+    /// the piece as it was before moving, a pawn if it promoted on arrival
+    pub piece: ColoredPiece,
+}
+
+/// A piece was taken off the board by a played move, en passant included.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PieceCaptured {
+    pub square: Square,
+    pub piece: ColoredPiece,
+}
+
+/// The pawn that just moved to `square` became `piece`.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PiecePromoted {
+    pub square: Square,
+    pub piece: ColoredPiece,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PieceChange {
+    Moved(PieceMoved),
+    Captured(PieceCaptured),
+    Promoted(PiecePromoted),
+}
+
+fn colored_piece_on(board: &Board, square: Square) -> Option<ColoredPiece> {
+    Some(ColoredPiece {
+        piece: board.piece_on(square)?,
+        color: board.color_on(square)?,
+    })
+}
+
+// what happened to the pieces between two positions one move apart: for each side, the pieces
+// that left a square are paired up with the ones that arrived on one, by piece type first and
+// then whatever is left for promotions; the ones that left for nowhere were captured
+fn piece_changes(before: &Board, after: &Board) -> Vec<PieceChange> {
+    let mut changes = Vec::new();
+
+    for color in [Color::White, Color::Black] {
+        let piece_on = |board: &Board, square| {
+            colored_piece_on(board, square).filter(|piece| piece.color == color)
+        };
+        let changed = |square| piece_on(before, square) != piece_on(after, square);
+
+        let mut left: Vec<_> = ALL_SQUARES
+            .into_iter()
+            .filter(|square| piece_on(before, *square).is_some() && changed(*square))
+            .collect();
+        let arrived = ALL_SQUARES
+            .into_iter()
+            .filter(|square| piece_on(after, *square).is_some() && changed(*square));
+
+        let mut promoted = Vec::new();
+        for to in arrived {
+            match left
+                .iter()
+                .position(|from| piece_on(before, *from) == piece_on(after, to))
+            {
+                Some(index) => {
+                    let from = left.remove(index);
+                    changes.push(PieceChange::Moved(PieceMoved {
+                        from,
+                        to,
+                        piece: piece_on(before, from).expect("left a square"),
+                    }));
+                }
+                None => promoted.push(to),
+            }
+        }
+
+        for to in promoted {
+            let Some(index) = left.iter().position(|from| {
+                piece_on(before, *from).is_some_and(|piece| piece.piece == Piece::Pawn)
+            }) else {
+                continue;
+            };
+            let from = left.remove(index);
+            changes.push(PieceChange::Moved(PieceMoved {
+                from,
+                to,
+                piece: piece_on(before, from).expect("left a square"),
+            }));
+            changes.push(PieceChange::Promoted(PiecePromoted {
+                square: to,
+                piece: piece_on(after, to).expect("arrived on a square"),
+            }));
+        }
+
+        changes.extend(left.into_iter().map(|square| {
+            PieceChange::Captured(PieceCaptured {
+                square,
+                piece: piece_on(before, square).expect("left a square"),
+            })
+        }));
+    }
+
+    changes
+}
+
+/// Triggers [`PieceMoved`], [`PieceCaptured`] and [`PiecePromoted`] for the move that turned
+/// `before` into `after`.
+pub(super) fn trigger_piece_changes(before: &Board, after: &Board, commands: &mut Commands) {
+    for change in piece_changes(before, after) {
+        match change {
+            PieceChange::Moved(event) => commands.trigger(event),
+            PieceChange::Captured(event) => commands.trigger(event),
+            PieceChange::Promoted(event) => commands.trigger(event),
+        }
+    }
+}
+
+pub(super) fn setup_sync(app: &mut App) {
+    // only squares whose piece changed are touched, so OnInsert/OnRemove observers of
+    // ColoredPiece see exactly what moved
+    app.add_observer(
+        |_: Trigger<PieceUpdateQueued>,
+         board: Res<Board>,
+         squares: Query<(Entity, &Square, Option<&ColoredPiece>)>,
+         mut commands: Commands| {
+            for (entity, square, current) in squares.iter() {
+                let wanted = colored_piece_on(&board, *square);
+                if current.copied() == wanted {
+                    continue;
+                }
+
+                match wanted {
+                    Some(piece) => {
+                        commands.entity(entity).insert(piece);
+                    }
+                    None => {
+                        commands.entity(entity).remove::<ColoredPiece>();
+                    }
+                }
+            }
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(fen: &str, uci: &str) -> Vec<PieceChange> {
+        let before = Board::from_fen(fen).unwrap();
+        let mut after = before.clone();
+        after.play_unchecked(before.parse_uci(uci).unwrap());
+        piece_changes(&before, &after)
+    }
+
+    fn white(piece: Piece) -> ColoredPiece {
+        ColoredPiece {
+            piece,
+            color: Color::White,
+        }
+    }
+    fn black(piece: Piece) -> ColoredPiece {
+        ColoredPiece {
+            piece,
+            color: Color::Black,
+        }
+    }
+
+    #[test]
+    fn tells_what_each_move_did_to_the_pieces() {
+        assert_eq!(
+            changes("r3k3/8/8/8/8/8/8/4K2R w K - 0 1", "e1g1"),
+            [
+                PieceChange::Moved(PieceMoved {
+                    from: Square::H1,
+                    to: Square::F1,
+                    piece: white(Piece::Rook),
+                }),
+                PieceChange::Moved(PieceMoved {
+                    from: Square::E1,
+                    to: Square::G1,
+                    piece: white(Piece::King),
+                }),
+            ]
+        );
+
+        assert_eq!(
+            changes("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"),
+            [
+                PieceChange::Moved(PieceMoved {
+                    from: Square::E5,
+                    to: Square::D6,
+                    piece: white(Piece::Pawn),
+                }),
+                PieceChange::Captured(PieceCaptured {
+                    square: Square::D5,
+                    piece: black(Piece::Pawn),
+                }),
+            ]
+        );
+
+        assert_eq!(
+            changes("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7b8n"),
+            [
+                PieceChange::Moved(PieceMoved {
+                    from: Square::A7,
+                    to: Square::B8,
+                    piece: white(Piece::Pawn),
+                }),
+                PieceChange::Promoted(PiecePromoted {
+                    square: Square::B8,
+                    piece: white(Piece::Knight),
+                }),
+                PieceChange::Captured(PieceCaptured {
+                    square: Square::B8,
+                    piece: black(Piece::Rook),
+                }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::{
+    ffi::OsStr,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::{
+        Mutex,
+        mpsc::{self, Receiver, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use derive_more::{Display, Error, From};
+
+use crate::chess_plugin::Score;
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Display, Error, From)]
+pub enum UciError {
+    #[display("could not talk to the engine: {_0}")]
+    Io(io::Error),
+    #[display("the engine did not answer {_0} in time")]
+    #[from(ignore)]
+    Timeout(#[error(not(source))] &'static str),
+    #[display("the engine exited")]
+    Closed,
+}
+
+/// A line the engine wrote, as far as we care about it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EngineMessage {
+    Id { name: String, value: String },
+    UciOk,
+    ReadyOk,
+    BestMove { mv: String, ponder: Option<String> },
+    Info(EngineInfo),
+    Other(String),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EngineInfo {
+    pub depth: Option<u32>,
+    pub score: Option<Score>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+    pub pv: Vec<String>,
+    pub string: Option<String>,
+}
+
+impl EngineMessage {
+    pub fn parse(line: &str) -> Self {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("id") => match tokens.next() {
+                Some(name) => EngineMessage::Id {
+                    name: name.to_string(),
+                    value: tokens.collect::<Vec<_>>().join(" "),
+                },
+                None => EngineMessage::Other(line.to_string()),
+            },
+            Some("uciok") => EngineMessage::UciOk,
+            Some("readyok") => EngineMessage::ReadyOk,
+            Some("bestmove") => match tokens.next() {
+                Some(mv) => EngineMessage::BestMove {
+                    mv: mv.to_string(),
+                    ponder: match (tokens.next(), tokens.next()) {
+                        (Some("ponder"), Some(ponder)) => Some(ponder.to_string()),
+                        _ => None,
+                    },
+                },
+                None => EngineMessage::Other(line.to_string()),
+            },
+            Some("info") => EngineMessage::Info(EngineInfo::parse(tokens)),
+            _ => EngineMessage::Other(line.to_string()),
+        }
+    }
+}
+
+impl EngineInfo {
+    fn parse<'a>(mut tokens: impl Iterator<Item = &'a str>) -> Self {
+        let mut info = EngineInfo::default();
+        while let Some(token) = tokens.next() {
+            match token {
+                "depth" => info.depth = tokens.next().and_then(|value| value.parse().ok()),
+                "nodes" => info.nodes = tokens.next().and_then(|value| value.parse().ok()),
+                "time" => {
+                    info.time = tokens
+                        .next()
+                        .and_then(|value| value.parse().ok())
+                        .map(Duration::from_millis);
+                }
+                "score" => {
+                    info.score = match (tokens.next(), tokens.next().map(str::parse)) {
+                        (Some("cp"), Some(Ok(value))) => Some(Score::Centipawns(value)),
+                        (Some("mate"), Some(Ok(value))) => Some(Score::Mate(value)),
+                        _ => None,
+                    };
+                }
+                // both run to the end of the line
+                "pv" => info.pv = tokens.by_ref().map(str::to_string).collect(),
+                "string" => info.string = Some(tokens.by_ref().collect::<Vec<_>>().join(" ")),
+                _ => {}
+            }
+        }
+        info
+    }
+}
+
+/// An engine running as a child process, spoken to over UCI.
+pub struct UciEngine {
+    child: Child,
+    stdin: ChildStdin,
+    // read on a thread of its own, so polling never blocks
+    lines: Mutex<Receiver<String>>,
+    name: Option<String>,
+}
+
+impl UciEngine {
+    /// Starts the engine and waits for it to complete the `uci`/`isready` handshake.
+    pub fn spawn<I, S>(program: impl AsRef<OsStr>, args: I) -> Result<Self, UciError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or(UciError::Closed)?;
+        let stdout = child.stdout.take().ok_or(UciError::Closed)?;
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Self {
+            child,
+            stdin,
+            lines: Mutex::new(receiver),
+            name: None,
+        };
+
+        engine.send("uci")?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        loop {
+            match engine.recv_until(deadline, "uci")? {
+                EngineMessage::Id { name, value } if name == "name" => engine.name = Some(value),
+                EngineMessage::UciOk => break,
+                _ => {}
+            }
+        }
+        engine.wait_until_ready()?;
+
+        Ok(engine)
+    }
+
+    /// What the engine calls itself.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn send(&mut self, command: &str) -> Result<(), UciError> {
+        writeln!(self.stdin, "{command}")?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), UciError> {
+        self.send(&format!("setoption name {name} value {value}"))
+    }
+
+    pub fn wait_until_ready(&mut self) -> Result<(), UciError> {
+        self.send("isready")?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        while self.recv_until(deadline, "isready")? != EngineMessage::ReadyOk {}
+        Ok(())
+    }
+
+    /// The next message, if the engine already wrote one.
+    pub fn try_recv(&self) -> Result<Option<EngineMessage>, UciError> {
+        match self.recv_timeout(Duration::ZERO) {
+            Err(UciError::Timeout(_)) => Ok(None),
+            message => message.map(Some),
+        }
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<EngineMessage, UciError> {
+        let lines = self.lines.lock().map_err(|_| UciError::Closed)?;
+        match lines.recv_timeout(timeout) {
+            Ok(line) => Ok(EngineMessage::parse(&line)),
+            Err(RecvTimeoutError::Timeout) => Err(UciError::Timeout("a command")),
+            Err(RecvTimeoutError::Disconnected) => Err(UciError::Closed),
+        }
+    }
+
+    fn recv_until(
+        &self,
+        deadline: Instant,
+        waiting_for: &'static str,
+    ) -> Result<EngineMessage, UciError> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        self.recv_timeout(timeout).map_err(|error| match error {
+            UciError::Timeout(_) => UciError::Timeout(waiting_for),
+            error => error,
+        })
+    }
+}
+
+impl Drop for UciEngine {
+    fn drop(&mut self) {
+        // ask nicely first, engines may want to clean up
+        let _ = self.send("quit");
+        let deadline = Instant::now() + Duration::from_millis(500);
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// A stand-in engine that always plays the first move it is told to (or e2e4).
+    pub(in crate::chess_plugin::uci) const FAKE_ENGINE: &str = r#"
+        move=e2e4
+        while read -r command rest; do
+            case "$command" in
+                uci) echo "id name Fake Engine"; echo "uciok" ;;
+                isready) echo "readyok" ;;
+                setoption) set -- $rest; [ "$2" = "BestMove" ] && move=$4 ;;
+                go) echo "info depth 1 score cp 13 nodes 20 pv $move"; echo "bestmove $move" ;;
+                quit) exit 0 ;;
+            esac
+        done
+    "#;
+
+    #[test]
+    fn parses_engine_output() {
+        assert_eq!(
+            EngineMessage::parse("bestmove e7e8q ponder e1e2"),
+            EngineMessage::BestMove {
+                mv: "e7e8q".to_string(),
+                ponder: Some("e1e2".to_string()),
+            }
+        );
+        assert_eq!(
+            EngineMessage::parse(
+                "info depth 12 seldepth 15 score mate -3 nodes 4000 time 35 pv e2e4 e7e5"
+            ),
+            EngineMessage::Info(EngineInfo {
+                depth: Some(12),
+                score: Some(Score::Mate(-3)),
+                nodes: Some(4000),
+                time: Some(Duration::from_millis(35)),
+                pv: vec!["e2e4".to_string(), "e7e5".to_string()],
+                string: None,
+            })
+        );
+    }
+
+    #[test]
+    fn talks_to_a_scripted_engine() {
+        let mut engine = UciEngine::spawn("sh", ["-c", FAKE_ENGINE]).unwrap();
+        assert_eq!(engine.name(), Some("Fake Engine"));
+
+        engine.set_option("BestMove", "d2d4").unwrap();
+        engine.send("position startpos").unwrap();
+        engine.send("go movetime 10").unwrap();
+
+        let best_move = loop {
+            match engine.recv_timeout(HANDSHAKE_TIMEOUT).unwrap() {
+                EngineMessage::BestMove { mv, .. } => break mv,
+                _ => continue,
+            }
+        };
+        assert_eq!(best_move, "d2d4");
+    }
+}
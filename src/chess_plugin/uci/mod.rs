@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{Board, ChessClock, Color, GameResult, Increment, MoveHistory, Resign};
+
+mod engine;
+pub use engine::*;
+
+/// Lets an external UCI engine play `color`. Spawn it as its own entity; the engine is asked for
+/// a move whenever it is `color`'s turn, and its `bestmove` is played through a
+/// [`MoveRequest`](super::MoveRequest). An illegal `bestmove` [`Resign`]s the game. Engines
+/// only get standard chess; under any other [`super::ChessVariant`] the player sits out.
+#[derive(Component)]
+pub struct UciPlayer {
+    pub color: Color,
+    /// how long to think about each move when there is no [`ChessClock`]
+    pub move_time: Duration,
+    engine: UciEngine,
+    /// the position the engine is searching
+    searching: Option<Board>,
+    stop_sent: bool,
+    /// whether the engine was told to expect Chess960 castling
+    chess960: bool,
+}
+
+impl UciPlayer {
+    pub fn new(color: Color, engine: UciEngine) -> Self {
+        Self {
+            color,
+            move_time: Duration::from_secs(1),
+            engine,
+            searching: None,
+            stop_sent: false,
+            chess960: false,
+        }
+    }
+
+    pub fn engine(&self) -> &UciEngine {
+        &self.engine
+    }
+}
+
+/// `position` command reaching `board` through the moves in `history`, so the engine can see
+/// repetitions.
+fn position_command(board: &Board, history: &MoveHistory) -> String {
+    let played = history.played();
+    let start = start_of(board, history);
+
+    let mut command = if *start == Board::default() {
+        "position startpos".to_string()
+    } else {
+        format!("position fen {}", start.to_fen())
+    };
+    if !played.is_empty() {
+        command.push_str(" moves");
+        for played in played {
+            command.push(' ');
+            command.push_str(&played.before.uci(played.mv));
+        }
+    }
+    command
+}
+
+fn start_of<'a>(board: &'a Board, history: &'a MoveHistory) -> &'a Board {
+    history
+        .played()
+        .first()
+        .map_or(board, |first| &first.before)
+}
+
+fn go_command(move_time: Duration, clock: Option<&ChessClock>, color: Color) -> String {
+    let Some(clock) = clock else {
+        return format!("go movetime {}", move_time.as_millis());
+    };
+
+    let increment = |color| match clock.increment(color) {
+        Increment::Fischer(increment) => increment.as_millis(),
+        _ => 0,
+    };
+    let mut command = format!(
+        "go wtime {} btime {} winc {} binc {}",
+        clock.remaining(Color::White).as_millis(),
+        clock.remaining(Color::Black).as_millis(),
+        increment(Color::White),
+        increment(Color::Black),
+    );
+    if let Some(moves) = clock.moves_to_go(color) {
+        command.push_str(&format!(" movestogo {moves}"));
+    }
+    command
+}
+
+fn drive_engine(
+    player: &mut UciPlayer,
+    board: &Board,
+    history: &MoveHistory,
+    result: &GameResult,
+    clock: Option<&ChessClock>,
+    commands: &mut Commands,
+) -> Result<(), UciError> {
+    while let Some(message) = player.engine.try_recv()? {
+        match message {
+            EngineMessage::BestMove { mv, .. } => {
+                let Some(position) = player.searching.take() else {
+                    continue;
+                };
+                player.stop_sent = false;
+                // the game moved on without us (undo, new game, ...)
+                if position != *board || result.is_over() {
+                    continue;
+                }
+                match board.parse_uci(&mv) {
+                    Ok(mv) if board.is_legal(mv) => commands.trigger(mv),
+                    // waiting for a move that will never come would hang the game
+                    _ => {
+                        warn!("engine played an illegal move: {mv}, forfeiting");
+                        commands.trigger(Resign(player.color));
+                    }
+                }
+            }
+            EngineMessage::Info(info) => debug!("engine: {info:?}"),
+            _ => {}
+        }
+    }
+
+    match &player.searching {
+        Some(position) if (position != board || result.is_over()) && !player.stop_sent => {
+            player.engine.send("stop")?;
+            player.stop_sent = true;
+        }
+        None if player.color == board.side_to_move() && !result.is_over() => {
+            let chess960 = start_of(board, history).is_chess960();
+            if chess960 != player.chess960 {
+                player
+                    .engine
+                    .set_option("UCI_Chess960", if chess960 { "true" } else { "false" })?;
+                player.chess960 = chess960;
+            }
+            player.engine.send(&position_command(board, history))?;
+            player
+                .engine
+                .send(&go_command(player.move_time, clock, player.color))?;
+            player.searching = Some(board.clone());
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn play_uci_engines(
+    mut players: Query<(Entity, &mut UciPlayer)>,
+    board: Res<Board>,
+    history: Res<MoveHistory>,
+    result: Res<GameResult>,
+    clock: Option<Res<ChessClock>>,
+    mut commands: Commands,
+) {
+    if !board.variant().standard_rules() {
+        if !players.is_empty() {
+            warn_once!(
+                "UCI engines only play standard chess, not {}",
+                board.variant().name()
+            );
+        }
+        return;
+    }
+
+    for (entity, mut player) in players.iter_mut() {
+        if let Err(error) = drive_engine(
+            &mut player,
+            &board,
+            &history,
+            &result,
+            clock.as_deref(),
+            &mut commands,
+        ) {
+            // leave the side to a human rather than bringing the game down
+            error!("{}: {error}", player.engine.name().unwrap_or("UCI engine"));
+            commands.entity(entity).remove::<UciPlayer>();
+        }
+    }
+}
+
+pub(super) fn setup_uci(app: &mut App) {
+    app.add_systems(Update, play_uci_engines);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Instant};
+
+    use super::{engine::tests::FAKE_ENGINE, *};
+    use crate::chess_plugin::{
+        ChessPlugin, Crazyhouse, GameEndReason, GameEnded, GameOutcome, MoveRequest, Square,
+    };
+
+    #[test]
+    fn describes_the_game_to_the_engine() {
+        let mut board = Board::default();
+        let mut history = MoveHistory::default();
+        for uci in ["e2e4", "e7e5", "g1f3"] {
+            let mv = board.parse_uci(uci).unwrap();
+            history.push(mv, board.clone());
+            board.play_unchecked(mv);
+        }
+
+        assert_eq!(
+            position_command(&board, &history),
+            "position startpos moves e2e4 e7e5 g1f3"
+        );
+        assert_eq!(
+            go_command(Duration::from_millis(500), None, Color::Black),
+            "go movetime 500"
+        );
+    }
+
+    #[test]
+    fn plays_the_engine_move() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, ChessPlugin::default()));
+        let engine = UciEngine::spawn("sh", ["-c", FAKE_ENGINE]).unwrap();
+        app.world_mut().spawn(UciPlayer::new(Color::White, engine));
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while *app.world().resource::<Board>() == Board::default() {
+            assert!(Instant::now() < deadline, "the engine never moved");
+            app.update();
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let history = app.world().resource::<MoveHistory>();
+        assert_eq!(
+            history.played()[0].mv,
+            MoveRequest::Move {
+                from: Square::E2,
+                to: Square::E4,
+                promotion: None,
+            }
+        );
+    }
+
+    #[test]
+    fn forfeits_on_an_illegal_move() {
+        for best_move in ["e2e5", "a3a4", "nonsense"] {
+            let mut app = App::new();
+            app.add_plugins((MinimalPlugins, ChessPlugin::default()));
+            let mut engine = UciEngine::spawn("sh", ["-c", FAKE_ENGINE]).unwrap();
+            engine.set_option("BestMove", best_move).unwrap();
+            app.world_mut().spawn(UciPlayer::new(Color::White, engine));
+
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while !app.world().resource::<GameResult>().is_over() {
+                assert!(Instant::now() < deadline, "the game hung after {best_move}");
+                app.update();
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            assert_eq!(
+                **app.world().resource::<GameResult>(),
+                Some(GameEnded {
+                    outcome: GameOutcome::Win(Color::Black),
+                    reason: GameEndReason::Resignation,
+                })
+            );
+            assert!(app.world().resource::<MoveHistory>().played().is_empty());
+        }
+    }
+
+    #[test]
+    fn sits_out_other_variants() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            ChessPlugin {
+                variant: std::sync::Arc::new(Crazyhouse),
+            },
+        ));
+        let engine = UciEngine::spawn("sh", ["-c", FAKE_ENGINE]).unwrap();
+        app.world_mut().spawn(UciPlayer::new(Color::White, engine));
+
+        for _ in 0..20 {
+            app.update();
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert!(app.world().resource::<MoveHistory>().played().is_empty());
+    }
+}
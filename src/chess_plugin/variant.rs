@@ -0,0 +1,250 @@
+use super::{Board, Color, GameStatus, MoveRequest, Square};
+
+/// The rules a game is played by, on top of the standard ones.
+///
+/// A variant lists the moves that may be played and decides when the game is over. [`Board`]
+/// runs on `cozy_chess` though, which holds exactly one king a side and plays only standard moves
+/// and drops from the pockets. A variant can list a part of those and decide games on its own
+/// terms, but variants like Horde, with no white king, or Antichess, where kings are taken, would
+/// need a board of their own.
+pub trait ChessVariant: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    /// Whether games are played by the standard rules alone, as the built-in search and UCI
+    /// engines play them.
+    fn standard_rules(&self) -> bool {
+        false
+    }
+
+    /// The position games start from, played by the standard rules until the plugin hands it
+    /// this variant.
+    fn start_position(&self) -> Board {
+        Board::default()
+    }
+
+    /// Whether pieces that are taken go to the pocket of the side that took them, to be dropped
+    /// back on the board with a [`MoveRequest::Drop`].
+    fn has_pockets(&self) -> bool {
+        false
+    }
+
+    /// Every move the side to move may play, in the form of [`Board::standard_legal_moves`].
+    /// [`Board::is_legal`], [`Board::has_legal_moves`] and the other move queries all go by
+    /// this list.
+    fn legal_moves(&self, board: &Board) -> Vec<MoveRequest> {
+        board.standard_legal_moves()
+    }
+
+    /// Whether the game goes on. [`GameStatus::Won`] is a win for the side that just moved.
+    fn status(&self, board: &Board) -> GameStatus {
+        board.standard_status()
+    }
+}
+
+/// Standard chess.
+pub struct Standard;
+
+impl ChessVariant for Standard {
+    fn name(&self) -> &'static str {
+        "Standard"
+    }
+
+    fn standard_rules(&self) -> bool {
+        true
+    }
+}
+
+/// Bringing the king to one of the four center squares also wins.
+pub struct KingOfTheHill;
+
+impl ChessVariant for KingOfTheHill {
+    fn name(&self) -> &'static str {
+        "King of the Hill"
+    }
+
+    fn status(&self, board: &Board) -> GameStatus {
+        let hill = [Square::D4, Square::E4, Square::D5, Square::E5];
+        if hill.contains(&board.king(board.side_to_move().opposite())) {
+            return GameStatus::Won;
+        }
+        board.standard_status()
+    }
+}
+
+/// Giving check a third time also wins.
+pub struct ThreeCheck;
+
+impl ChessVariant for ThreeCheck {
+    fn name(&self) -> &'static str {
+        "Three-check"
+    }
+
+    fn status(&self, board: &Board) -> GameStatus {
+        if board.checks_given(board.side_to_move().opposite()) >= 3 {
+            return GameStatus::Won;
+        }
+        board.standard_status()
+    }
+}
+
+impl ThreeCheck {
+    /// How many more checks `color` needs to win.
+    pub fn checks_left(board: &Board, color: Color) -> u8 {
+        3u8.saturating_sub(board.checks_given(color))
+    }
+}
+
+/// Taken pieces change sides and can be dropped back on an empty square instead of moving,
+/// pawns anywhere but the first and last ranks. A promoted piece goes back into the pocket as a
+/// pawn.
+pub struct Crazyhouse;
+
+impl ChessVariant for Crazyhouse {
+    fn name(&self) -> &'static str {
+        "Crazyhouse"
+    }
+
+    fn has_pockets(&self) -> bool {
+        true
+    }
+
+    // a drop can get out of what would otherwise be checkmate or stalemate, and with pieces
+    // always coming back there is no fifty-move rule
+    fn status(&self, board: &Board) -> GameStatus {
+        if board.has_legal_moves() {
+            GameStatus::Ongoing
+        } else if board.is_check() {
+            GameStatus::Won
+        } else {
+            GameStatus::Drawn
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::chess_plugin::{Piece, RejectionReason};
+
+    fn play(variant: Arc<dyn ChessVariant>, fen: &str, moves: &[&str]) -> Board {
+        let mut board = Board::from_fen(fen).unwrap().with_variant(variant);
+        for mv in moves {
+            let mv = board.parse_uci(mv).unwrap();
+            assert!(board.is_legal(mv));
+            board.play_unchecked(mv);
+        }
+        board
+    }
+
+    /// Standard chess where taking is compulsory, as in Antichess.
+    struct ForcedCaptures;
+
+    impl ChessVariant for ForcedCaptures {
+        fn name(&self) -> &'static str {
+            "Forced captures"
+        }
+
+        fn legal_moves(&self, board: &Board) -> Vec<MoveRequest> {
+            let moves = board.standard_legal_moves();
+            let captures: Vec<_> = moves
+                .iter()
+                .copied()
+                .filter(|mv| board.is_capture(*mv))
+                .collect();
+            if captures.is_empty() { moves } else { captures }
+        }
+    }
+
+    #[test]
+    fn variants_list_the_moves_that_may_be_played() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let board = play(Arc::new(ForcedCaptures), start, &["e2e4", "d7d5"]);
+
+        let take = board.parse_uci("e4d5").unwrap();
+        let push = board.parse_uci("e4e5").unwrap();
+        assert_eq!(board.legal_moves(), [take]);
+        assert!(!board.is_legal(push));
+        assert_eq!(board.validate_move(push), Err(RejectionReason::IllegalMove));
+        assert!(board.legal_moves_from(Square::G1).is_empty());
+        assert!(matches!(board.status(), GameStatus::Ongoing));
+    }
+
+    #[test]
+    fn variants_end_games_their_own_way() {
+        let start = "4k3/8/8/8/8/8/3K4/8 w - - 0 1";
+        let board = play(Arc::new(KingOfTheHill), start, &["d2d3", "e8e7"]);
+        assert!(matches!(board.status(), GameStatus::Ongoing));
+        let board = play(Arc::new(KingOfTheHill), start, &["d2d3", "e8e7", "d3d4"]);
+        assert!(matches!(board.status(), GameStatus::Won));
+        // the same moves mean nothing in standard chess
+        let board = play(Arc::new(Standard), start, &["d2d3", "e8e7", "d3d4"]);
+        assert!(matches!(board.status(), GameStatus::Ongoing));
+
+        let rooks = "4k3/8/8/8/8/8/8/R3K3 w - - 0 1";
+        let checks = ["a1a8", "e8e7", "a8a7", "e7e6", "a7a6"];
+        let board = play(Arc::new(ThreeCheck), rooks, &checks[..4]);
+        assert_eq!(ThreeCheck::checks_left(&board, Color::White), 1);
+        assert!(matches!(board.status(), GameStatus::Ongoing));
+        let board = play(Arc::new(ThreeCheck), rooks, &checks);
+        assert!(matches!(board.status(), GameStatus::Won));
+    }
+
+    #[test]
+    fn crazyhouse_drops_captured_pieces() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let board = play(
+            Arc::new(Crazyhouse),
+            start,
+            &["e2e4", "d7d5", "e4d5", "d8d5", "P@d4"],
+        );
+        assert_eq!(board.piece_on(Square::D4), Some(Piece::Pawn));
+        assert_eq!(board.pocket_count(Color::White, Piece::Pawn), 0);
+        assert_eq!(board.pocket_count(Color::Black, Piece::Pawn), 1);
+        assert_eq!(board.san(board.parse_san("@d6").unwrap()), "P@d6");
+
+        let drop = |piece, to| MoveRequest::Drop { piece, to };
+        assert!(board.is_legal(drop(Piece::Pawn, Square::D6)));
+        // not on the last rank, nor onto another piece
+        assert_eq!(
+            board.validate_move(drop(Piece::Pawn, Square::D8)),
+            Err(RejectionReason::IllegalMove)
+        );
+        assert!(!board.is_legal(drop(Piece::Pawn, Square::D5)));
+        assert_eq!(
+            board.validate_move(drop(Piece::Knight, Square::D6)),
+            Err(RejectionReason::NoPieceOnSource)
+        );
+
+        // pieces taken in standard chess are gone for good
+        let board = play(Arc::new(Standard), start, &["e2e4", "d7d5", "e4d5"]);
+        assert!(board.legal_drops().is_empty());
+
+        // the queen goes back into the pocket as the pawn it was
+        let promotion = "r3k3/1P6/8/8/8/8/8/4K2b w - - 0 1";
+        let board = play(Arc::new(Crazyhouse), promotion, &["b7a8q", "h1a8"]);
+        assert_eq!(board.pocket(Color::White)[3], (Piece::Rook, 1));
+        assert_eq!(board.pocket(Color::Black)[0], (Piece::Pawn, 1));
+        assert_eq!(board.pocket_count(Color::Black, Piece::Queen), 0);
+    }
+
+    #[test]
+    fn drops_on_top_of_a_full_set() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        // white still has all 16 pieces, and 8 pawns, with a black pawn in the pocket
+        let moves = ["e2e4", "d7d5", "e4d5", "g8f6", "P@e4"];
+        let board = play(Arc::new(Crazyhouse), start, &moves);
+        assert_eq!(board.piece_on(Square::E4), Some(Piece::Pawn));
+        assert_eq!(board.pocket_count(Color::White, Piece::Pawn), 0);
+
+        // and the ninth pawn plays on
+        let board = play(
+            Arc::new(Crazyhouse),
+            start,
+            &[&moves[..], &["c7c6", "e4e5"]].concat(),
+        );
+        assert_eq!(board.piece_on(Square::E5), Some(Piece::Pawn));
+        assert!(Board::from_fen(&board.to_fen()).is_ok());
+    }
+}
@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use bevy_game_2::chess_plugin::{Board, ColoredPiece, MoveRequest, Square};
+
+use crate::{
+    BoardSquare, PIECE_SPRITE_SIZE, PieceAssets, animation::PieceAnimation,
+    highlights::HighlightColors, move_list::HistoryView, orientation::Perspective,
+    spawn_promotion_picker, square_to_xy,
+};
+
+/// How pieces are moved with the pointer.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputMode {
+    /// drag a piece onto its destination
+    Drag,
+    /// click a piece, then its destination
+    Click,
+    #[default]
+    DragAndClick,
+}
+
+impl InputMode {
+    pub fn drag(self) -> bool {
+        matches!(self, InputMode::Drag | InputMode::DragAndClick)
+    }
+    pub fn click(self) -> bool {
+        matches!(self, InputMode::Click | InputMode::DragAndClick)
+    }
+}
+
+/// The square of the piece picked by clicking on it, waiting for a click on its destination.
+#[derive(Resource, Default)]
+struct Selection(Option<Square>);
+
+#[derive(Component)]
+struct SelectionHighlight;
+
+pub struct ClickToMovePlugin;
+
+impl Plugin for ClickToMovePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputMode>()
+            .init_resource::<Selection>()
+            .add_systems(
+                Update,
+                (
+                    // whatever was selected may have moved or been taken
+                    clear_selection.run_if(resource_changed::<Board>),
+                    highlight_selection.run_if(
+                        resource_changed::<Selection>
+                            .or(resource_changed::<Perspective>)
+                            .or(resource_changed::<HighlightColors>),
+                    ),
+                )
+                    .chain(),
+            )
+            .add_observer(click_square);
+    }
+}
+
+#[expect(clippy::too_many_arguments)]
+fn click_square(
+    click: Trigger<Pointer<Click>>,
+    squares: Query<&BoardSquare>,
+    pieces: Query<(Entity, &Square), With<ColoredPiece>>,
+    visibility: Query<&Visibility>,
+    input_mode: Res<InputMode>,
+    board: Res<Board>,
+    piece_assets: Res<PieceAssets>,
+    perspective: Res<Perspective>,
+    animations: Query<(), With<PieceAnimation>>,
+    view: Res<HistoryView>,
+    mut selection: ResMut<Selection>,
+    mut commands: Commands,
+) {
+    let Ok(&BoardSquare(square)) = squares.get(click.target()) else {
+        return;
+    };
+    if !input_mode.click() || !animations.is_empty() || !view.is_live() {
+        return;
+    }
+
+    let own_piece = board.color_on(square) == Some(board.side_to_move());
+    let Some(from) = selection.0 else {
+        if own_piece {
+            selection.0 = Some(square);
+        }
+        return;
+    };
+
+    if square == from {
+        selection.0 = None;
+        return;
+    }
+    let mv = MoveRequest::Move {
+        from,
+        to: square,
+        promotion: None,
+    };
+    // clicking its own rook after the king castles
+    if own_piece && !board.is_legal(mv) {
+        selection.0 = Some(square);
+        return;
+    }
+
+    selection.0 = None;
+
+    if board.needs_promotion(mv) {
+        let Some((moved_piece, _)) = pieces.iter().find(|(_, square)| **square == from) else {
+            return;
+        };
+        spawn_promotion_picker(
+            &piece_assets,
+            &board,
+            perspective.0,
+            moved_piece,
+            visibility,
+            &mut commands,
+            mv,
+        );
+        return;
+    }
+
+    commands.trigger(mv);
+}
+
+fn clear_selection(mut selection: ResMut<Selection>) {
+    if selection.0.is_some() {
+        selection.0 = None;
+    }
+}
+
+fn highlight_selection(
+    selection: Res<Selection>,
+    colors: Res<HighlightColors>,
+    perspective: Res<Perspective>,
+    highlights: Query<Entity, With<SelectionHighlight>>,
+    mut commands: Commands,
+) {
+    for highlight in highlights.iter() {
+        commands.entity(highlight).despawn();
+    }
+
+    if let Some(square) = selection.0 {
+        commands.spawn((
+            SelectionHighlight,
+            Sprite::from_color(colors.selection, Vec2::splat(PIECE_SPRITE_SIZE)),
+            Transform::from_translation(square_to_xy(square, perspective.0).extend(0.25)),
+            Pickable::IGNORE,
+        ));
+    }
+}
@@ -0,0 +1,161 @@
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_game_2::chess_plugin::{self, ALL_SQUARES, Square};
+
+use crate::{PIECE_SPRITE_SIZE, orientation::Perspective, square_to_xy, theme::SquareColors};
+
+/// Where the a–h and 1–8 labels go. Ctrl+L cycles through them.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CoordinateLabels {
+    /// in the corners of the bottom rank and the left file, in the other square color
+    #[default]
+    Inside,
+    /// under the board and along its left edge
+    Outside,
+    Hidden,
+}
+
+#[derive(Component, Clone, Copy)]
+enum CoordinateLabel {
+    /// a file, from 0 for a to 7 for h
+    File(u8),
+    /// a rank, from 0 for 1 to 7 for 8
+    Rank(u8),
+}
+
+impl CoordinateLabel {
+    fn text(self) -> String {
+        match self {
+            CoordinateLabel::File(file) => char::from(b'a' + file).to_string(),
+            CoordinateLabel::Rank(rank) => char::from(b'1' + rank).to_string(),
+        }
+    }
+
+    /// The square along the bottom rank, or the left file, this label belongs to.
+    fn square(self, perspective: chess_plugin::Color) -> Square {
+        let edge = match perspective {
+            chess_plugin::Color::White => 0,
+            chess_plugin::Color::Black => 7,
+        };
+        let (file, rank) = match self {
+            CoordinateLabel::File(file) => (file, edge),
+            CoordinateLabel::Rank(rank) => (edge, rank),
+        };
+        ALL_SQUARES[usize::from(rank * 8 + file)]
+    }
+}
+
+pub struct CoordinatesPlugin;
+
+impl Plugin for CoordinatesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CoordinateLabels>()
+            .add_systems(Startup, spawn_coordinate_labels)
+            .add_systems(
+                Update,
+                (
+                    cycle_coordinate_labels,
+                    layout_coordinate_labels.run_if(
+                        resource_changed::<CoordinateLabels>
+                            .or(resource_changed::<Perspective>)
+                            .or(resource_changed::<SquareColors>),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn spawn_coordinate_labels(mut commands: Commands) {
+    let labels =
+        (0..8).flat_map(|index| [CoordinateLabel::File(index), CoordinateLabel::Rank(index)]);
+    for label in labels {
+        commands.spawn((
+            label,
+            Text2d::new(label.text()),
+            TextFont {
+                font_size: PIECE_SPRITE_SIZE * 0.18,
+                ..Default::default()
+            },
+            TextColor::default(),
+            Anchor::default(),
+            Transform::default(),
+            Visibility::Hidden,
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+fn cycle_coordinate_labels(keys: Res<ButtonInput<KeyCode>>, mut labels: ResMut<CoordinateLabels>) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.just_pressed(KeyCode::KeyL)
+    {
+        return;
+    }
+
+    *labels = match *labels {
+        CoordinateLabels::Inside => CoordinateLabels::Outside,
+        CoordinateLabels::Outside => CoordinateLabels::Hidden,
+        CoordinateLabels::Hidden => CoordinateLabels::Inside,
+    };
+}
+
+fn layout_coordinate_labels(
+    placement: Res<CoordinateLabels>,
+    perspective: Res<Perspective>,
+    colors: Res<SquareColors>,
+    mut labels: Query<(
+        &CoordinateLabel,
+        &mut Transform,
+        &mut Anchor,
+        &mut TextColor,
+        &mut Visibility,
+    )>,
+) {
+    let half = PIECE_SPRITE_SIZE * 0.5;
+    let margin = PIECE_SPRITE_SIZE * 0.06;
+
+    for (label, mut transform, mut anchor, mut color, mut visibility) in labels.iter_mut() {
+        let square = label.square(perspective.0);
+        let (offset, label_anchor, label_color) = match (*placement, label) {
+            (CoordinateLabels::Hidden, _) => {
+                *visibility = Visibility::Hidden;
+                continue;
+            }
+            // the color of the square next to it stands out on this one
+            (CoordinateLabels::Inside, CoordinateLabel::File(_)) => (
+                Vec2::new(half - margin, -half + margin),
+                Anchor::BottomRight,
+                contrasting(&colors, square),
+            ),
+            (CoordinateLabels::Inside, CoordinateLabel::Rank(_)) => (
+                Vec2::new(-half + margin, half - margin),
+                Anchor::TopLeft,
+                contrasting(&colors, square),
+            ),
+            (CoordinateLabels::Outside, CoordinateLabel::File(_)) => (
+                Vec2::new(0.0, -half - margin),
+                Anchor::TopCenter,
+                colors.light,
+            ),
+            (CoordinateLabels::Outside, CoordinateLabel::Rank(_)) => (
+                Vec2::new(-half - margin, 0.0),
+                Anchor::CenterRight,
+                colors.light,
+            ),
+        };
+
+        // above the squares and their highlights, below the pieces
+        transform.translation = (square_to_xy(square, perspective.0) + offset).extend(0.3);
+        *anchor = label_anchor;
+        color.0 = label_color;
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn contrasting(colors: &SquareColors, square: Square) -> Color {
+    if colors.of(square) == colors.light {
+        colors.dark
+    } else {
+        colors.light
+    }
+}
@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+use bevy_game_2::chess_plugin::{Board, MoveHistory};
+
+use crate::{BoardSquare, PIECE_SPRITE_SIZE};
+
+/// On the board squares the last move was played from and to.
+#[derive(Component)]
+pub struct LastMoveSquare;
+
+/// On the board square of the king in check.
+#[derive(Component)]
+pub struct CheckSquare;
+
+/// Colors of the square highlights, for themes to change.
+#[derive(Resource, Clone, Debug)]
+pub struct HighlightColors {
+    pub last_move: Color,
+    pub check: Color,
+    /// the dots and rings on the squares the held piece may go to
+    pub move_hint: Color,
+    /// the square picked up by click-to-move
+    pub selection: Color,
+}
+
+impl Default for HighlightColors {
+    fn default() -> Self {
+        Self {
+            last_move: Color::srgba(0.85, 0.75, 0.2, 0.45),
+            check: Color::srgba(0.9, 0.1, 0.1, 0.8),
+            move_hint: Color::srgba(0.2, 0.55, 0.25, 0.7),
+            selection: Color::srgba(0.95, 0.8, 0.2, 0.5),
+        }
+    }
+}
+
+#[derive(Component)]
+struct LastMoveTint;
+
+#[derive(Component)]
+struct CheckGlow {
+    /// how much of [`HighlightColors::check`]'s alpha this ring keeps
+    alpha: f32,
+}
+
+#[derive(Resource)]
+struct CheckGlowMesh(Handle<Mesh>);
+
+pub struct HighlightsPlugin;
+
+impl Plugin for HighlightsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HighlightColors>()
+            .add_systems(Startup, load_check_glow_mesh)
+            .add_systems(
+                Update,
+                (
+                    mark_highlighted_squares.run_if(resource_changed::<Board>),
+                    restyle_highlights.run_if(resource_changed::<HighlightColors>),
+                ),
+            )
+            .add_observer(add_last_move_tint)
+            .add_observer(add_check_glow)
+            .add_observer(remove_overlay::<LastMoveSquare, LastMoveTint>)
+            .add_observer(remove_overlay::<CheckSquare, CheckGlow>);
+    }
+}
+
+fn load_check_glow_mesh(mut meshes: ResMut<Assets<Mesh>>, mut commands: Commands) {
+    commands.insert_resource(CheckGlowMesh(
+        meshes.add(Circle::new(PIECE_SPRITE_SIZE * 0.5)),
+    ));
+}
+
+fn mark_highlighted_squares(
+    board: Res<Board>,
+    history: Res<MoveHistory>,
+    squares: Query<(Entity, &BoardSquare, Has<LastMoveSquare>, Has<CheckSquare>)>,
+    mut commands: Commands,
+) {
+    let last_move = history.played().last().map(|played| played.mv);
+    let checked_king = board.is_check().then(|| board.king(board.side_to_move()));
+
+    for (entity, square, has_last_move, has_check) in squares.iter() {
+        let last_move =
+            last_move.is_some_and(|mv| mv.source() == Some(square.0) || mv.to() == square.0);
+        match (last_move, has_last_move) {
+            (true, false) => {
+                commands.entity(entity).insert(LastMoveSquare);
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<LastMoveSquare>();
+            }
+            _ => {}
+        }
+
+        let check = checked_king == Some(square.0);
+        match (check, has_check) {
+            (true, false) => {
+                commands.entity(entity).insert(CheckSquare);
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<CheckSquare>();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn add_last_move_tint(
+    trigger: Trigger<OnAdd, LastMoveSquare>,
+    colors: Res<HighlightColors>,
+    mut commands: Commands,
+) {
+    commands.entity(trigger.target()).with_child((
+        LastMoveTint,
+        Sprite::from_color(colors.last_move, Vec2::splat(PIECE_SPRITE_SIZE)),
+        Transform::from_xyz(0.0, 0.0, 0.1),
+        Pickable::IGNORE,
+    ));
+}
+
+fn add_check_glow(
+    trigger: Trigger<OnAdd, CheckSquare>,
+    colors: Res<HighlightColors>,
+    mesh: Res<CheckGlowMesh>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+) {
+    commands.entity(trigger.target()).with_children(|parent| {
+        // a few fading rings make do for a glow
+        for (scale, alpha) in [(1.0, 0.35), (0.8, 0.6), (0.6, 1.0)] {
+            parent.spawn((
+                CheckGlow { alpha },
+                Mesh2d(mesh.0.clone()),
+                MeshMaterial2d(materials.add(faded(colors.check, alpha))),
+                Transform::from_xyz(0.0, 0.0, 0.2).with_scale(Vec3::splat(scale)),
+                Pickable::IGNORE,
+            ));
+        }
+    });
+}
+
+fn remove_overlay<Marker: Component, Overlay: Component>(
+    trigger: Trigger<OnRemove, Marker>,
+    children: Query<&Children>,
+    overlays: Query<Entity, With<Overlay>>,
+    mut commands: Commands,
+) {
+    let Ok(children) = children.get(trigger.target()) else {
+        return;
+    };
+    for overlay in overlays.iter_many(children) {
+        commands.entity(overlay).despawn();
+    }
+}
+
+fn restyle_highlights(
+    colors: Res<HighlightColors>,
+    mut tints: Query<&mut Sprite, With<LastMoveTint>>,
+    glows: Query<(&CheckGlow, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for mut sprite in tints.iter_mut() {
+        sprite.color = colors.last_move;
+    }
+    for (glow, material) in glows.iter() {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = faded(colors.check, glow.alpha);
+        }
+    }
+}
+
+fn faded(color: Color, alpha: f32) -> Color {
+    color.with_alpha(color.alpha() * alpha)
+}
@@ -1,5 +1,5 @@
 use indexmap::IndexSet;
-use slotmap::{DefaultKey, SecondaryMap, SlotMap, new_key_type};
+use slotmap::{DefaultKey, SecondaryMap, SlotMap};
 
 pub struct PriorotyIndexSlotMap<T> {
     slot_map: SlotMap<DefaultKey, T>,
@@ -8,6 +8,7 @@ pub struct PriorotyIndexSlotMap<T> {
 }
 
 impl<T> PriorotyIndexSlotMap<T> {
+    #[expect(dead_code)]
     pub fn new() -> Self {
         Self {
             slot_map: SlotMap::new(),
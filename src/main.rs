@@ -1,26 +1,72 @@
-mod chess_plugin;
+mod animation;
+mod captured;
+mod click_to_move;
+mod coordinates;
 mod cursor_style;
+mod highlights;
+
+mod move_hints;
+mod move_input;
+mod move_list;
+mod orientation;
+mod pockets;
+mod theme;
 
 mod index_slot_map;
 
+use std::{sync::Arc, time::Duration};
+
 use bevy::{
     dev_tools::fps_overlay::FpsOverlayPlugin,
     ecs::relationship::{RelatedSpawnerCommands, Relationship},
     prelude::*,
+    render::camera::ScalingMode,
     window::SystemCursorIcon,
     winit::cursor::CursorIcon,
 };
 
-use chess_plugin::{ALL_SQUARES, Board, ChessPlugin, ColoredPiece, MoveRequest, Piece, Square};
+use animation::{AnimationPlugin, DroppedPiece, PieceAnimation};
+use anyhow::Context;
+use bevy_game_2::chess_plugin::{
+    self, ALL_SQUARES, AgreeDraw, AiPlayer, Board, ChessClock, ChessPlugin, ChessVariant,
+    ColoredPiece, Crazyhouse, GameEnded, GameResult, KingOfTheHill, LoadGame, LoadPosition,
+    MoveHistory, MoveRejected, MoveRequest, NewGame, Pgn, Piece, RedoMove, Resign, Square,
+    Standard, StartingPosition, ThreeCheck, TimeControl, UciEngine, UciPlayer, UndoMove,
+};
+use captured::CapturedPlugin;
+use click_to_move::{ClickToMovePlugin, InputMode};
+use coordinates::CoordinatesPlugin;
 use cursor_style::{CursorContext, OnClick, OnHover};
+use highlights::HighlightsPlugin;
+use move_hints::MoveHintsPlugin;
+use move_input::MoveInputPlugin;
+use move_list::{HistoryView, MoveListPlugin};
+use orientation::{BoardOrientation, OrientationPlugin, Perspective};
+use pockets::{PocketPiece, PocketsPlugin};
+use theme::{SquareColors, ThemePlugin};
+
+fn main() -> anyhow::Result<()> {
+    let args = parse_args(std::env::args().skip(1))?;
 
-fn main() {
     let mut app = App::new();
     app.add_plugins((
         FpsOverlayPlugin::default(),
         DefaultPlugins,
         MeshPickingPlugin,
-        ChessPlugin,
+        ChessPlugin {
+            variant: args.variant.clone(),
+        },
+        MoveHintsPlugin,
+        ClickToMovePlugin,
+        OrientationPlugin,
+        HighlightsPlugin,
+        AnimationPlugin,
+        ThemePlugin,
+        CoordinatesPlugin,
+        CapturedPlugin,
+        MoveListPlugin,
+        MoveInputPlugin,
+        PocketsPlugin,
     ))
     .insert_resource(SpritePickingSettings {
         picking_mode: SpritePickingMode::BoundingBox,
@@ -29,13 +75,293 @@ fn main() {
     .insert_resource(CursorContext::init(CursorIcon::System(
         SystemCursorIcon::Default,
     )))
-    .add_systems(Startup, (load_assets, setup.after(load_assets)));
+    .add_systems(Startup, (load_assets, setup.after(load_assets)))
+    .add_systems(
+        Update,
+        (
+            log_fen,
+            undo_redo,
+            save_game,
+            game_controls,
+            shake_rejected,
+            update_clock_displays,
+        ),
+    )
+    .add_observer(|event: Trigger<GameEnded>| {
+        info!("game over: {:?} by {:?}", event.outcome, event.reason);
+    })
+    .add_observer(
+        |event: Trigger<MoveRejected>,
+         pieces: Query<(Entity, &Square), With<ColoredPiece>>,
+         mut commands: Commands| {
+            info!("move rejected: {:?}", event.reason);
+
+            if let Some((entity, _)) = pieces
+                .iter()
+                .find(|(_, square)| Some(**square) == event.request.source())
+            {
+                commands
+                    .entity(entity)
+                    .insert(Shake(Timer::from_seconds(0.3, TimerMode::Once)));
+            }
+        },
+    );
+
+    if let Some(color) = args.ai {
+        app.world_mut().spawn(AiPlayer::new(color));
+    }
+    // sit on the human's side of the board
+    let computer = args.ai.or(args.engine.as_ref().map(|(color, _)| *color));
+    if computer == Some(chess_plugin::Color::White) {
+        app.insert_resource(BoardOrientation::Black);
+    }
+    if let Some((color, path)) = args.engine {
+        let engine = UciEngine::spawn(&path, std::iter::empty::<&str>())
+            .with_context(|| format!("could not start {path}"))?;
+        info!("{} plays {color:?}", engine.name().unwrap_or(&path));
+        app.world_mut().spawn(UciPlayer::new(color, engine));
+    }
+
+    if let Some(time_control) = args.time_control {
+        app.insert_resource(ChessClock::new(time_control));
+    }
+    if let Some(input_mode) = args.input_mode {
+        app.insert_resource(input_mode);
+    }
+    if let Some(starting_position) = args.starting_position {
+        app.insert_resource(starting_position);
+    }
+
+    if let Some(start) = args.start {
+        app.add_systems(
+            Startup,
+            (move |mut commands: Commands| match start.clone() {
+                Start::Position(board) => commands.trigger(LoadPosition(board)),
+                Start::Game(pgn) => commands.trigger(LoadGame(pgn)),
+            })
+            .after(setup),
+        );
+    }
+
     app.run();
+    Ok(())
+}
+
+struct Args {
+    start: Option<Start>,
+    ai: Option<chess_plugin::Color>,
+    /// a UCI engine executable, and the side it plays
+    engine: Option<(chess_plugin::Color, String)>,
+    input_mode: Option<InputMode>,
+    starting_position: Option<StartingPosition>,
+    variant: Arc<dyn ChessVariant>,
+    /// `None` plays without a clock
+    time_control: Option<TimeControl>,
 }
 
+// [--ai white|black] [--engine white|black path] [--input drag|click|both]
+// [--variant standard|koth|three-check|crazyhouse] [--time-control PGN time control|off]
+// [--chess960 0-959|random | FEN | path to a PGN file]
+fn parse_args(mut args: impl Iterator<Item = String>) -> anyhow::Result<Args> {
+    let mut parsed = Args {
+        start: None,
+        ai: None,
+        engine: None,
+        input_mode: None,
+        starting_position: None,
+        variant: Arc::new(Standard),
+        time_control: Some(TimeControl::fischer(
+            Duration::from_secs(5 * 60),
+            Duration::from_secs(3),
+        )),
+    };
+
+    while let Some(arg) = args.next() {
+        if arg == "--ai" {
+            parsed.ai = Some(parse_color(&arg, args.next())?);
+        } else if arg == "--engine" {
+            let color = parse_color(&arg, args.next())?;
+            let path = args.next().context("--engine needs a path to the engine")?;
+            parsed.engine = Some((color, path));
+        } else if arg == "--input" {
+            let mode = args.next().context("--input needs a mode")?;
+            parsed.input_mode = Some(match mode.as_str() {
+                "drag" => InputMode::Drag,
+                "click" => InputMode::Click,
+                "both" => InputMode::DragAndClick,
+                _ => anyhow::bail!("--input needs drag, click or both, not {mode}"),
+            });
+        } else if arg == "--variant" {
+            let variant = args.next().context("--variant needs a variant")?;
+            parsed.variant = match variant.as_str() {
+                "standard" => Arc::new(Standard),
+                "koth" => Arc::new(KingOfTheHill),
+                "three-check" => Arc::new(ThreeCheck),
+                "crazyhouse" => Arc::new(Crazyhouse),
+                _ => anyhow::bail!(
+                    "--variant needs standard, koth, three-check or crazyhouse, not {variant}"
+                ),
+            };
+        } else if arg == "--time-control" {
+            let control = args
+                .next()
+                .context("--time-control needs a time control or off")?;
+            parsed.time_control = match control.as_str() {
+                "off" => None,
+                _ => Some(
+                    control
+                        .parse()
+                        .context("--time-control needs a time control like 300+3, or off")?,
+                ),
+            };
+        } else if arg == "--chess960" {
+            let position = args
+                .next()
+                .context("--chess960 needs a position number or random")?;
+            parsed.starting_position = Some(match position.as_str() {
+                "random" => StartingPosition::RandomChess960,
+                _ => match position.parse() {
+                    Ok(index) if index < 960 => StartingPosition::Chess960(index),
+                    _ => anyhow::bail!("--chess960 needs 0 to 959 or random, not {position}"),
+                },
+            });
+        } else {
+            parsed.start = Some(parse_start(&arg)?);
+        }
+    }
+    if parsed.start.is_some() && parsed.starting_position.is_some() {
+        anyhow::bail!("--chess960 can't be combined with a starting FEN or PGN");
+    }
+    if parsed.ai.is_some() && !parsed.variant.standard_rules() {
+        anyhow::bail!(
+            "--ai only plays standard chess, not {}",
+            parsed.variant.name()
+        );
+    }
+    if parsed.engine.is_some() && !parsed.variant.standard_rules() {
+        anyhow::bail!(
+            "--engine only plays standard chess, not {}",
+            parsed.variant.name()
+        );
+    }
+
+    Ok(parsed)
+}
+
+fn parse_color(flag: &str, color: Option<String>) -> anyhow::Result<chess_plugin::Color> {
+    let color = color.with_context(|| format!("{flag} needs a color"))?;
+    Ok(match color.as_str() {
+        "white" => chess_plugin::Color::White,
+        "black" => chess_plugin::Color::Black,
+        _ => anyhow::bail!("{flag} needs white or black, not {color}"),
+    })
+}
+
+#[derive(Clone)]
+enum Start {
+    Position(Board),
+    Game(Pgn),
+}
+
+fn parse_start(arg: &str) -> anyhow::Result<Start> {
+    if arg.ends_with(".pgn") {
+        let pgn = std::fs::read_to_string(arg)
+            .with_context(|| format!("could not read {arg}"))?
+            .parse()
+            .with_context(|| format!("invalid PGN in {arg}"))?;
+        return Ok(Start::Game(pgn));
+    }
+
+    let board = Board::from_fen(arg).with_context(|| format!("invalid FEN: {arg}"))?;
+    Ok(Start::Position(board))
+}
+
+fn save_game(
+    keys: Res<ButtonInput<KeyCode>>,
+    history: Res<MoveHistory>,
+    board: Res<Board>,
+    result: Res<GameResult>,
+) -> Result {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.just_pressed(KeyCode::KeyS)
+    {
+        return Ok(());
+    }
+
+    let pgn = Pgn::from_history(&history, &board, &result);
+    let since_epoch = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?;
+    let path = format!("game-{}.pgn", since_epoch.as_secs());
+    std::fs::write(&path, pgn.to_string())?;
+    info!("saved game to {path}");
+
+    Ok(())
+}
+
+fn undo_redo(keys: Res<ButtonInput<KeyCode>>, mut commands: Commands) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::KeyZ) {
+        commands.trigger(UndoMove);
+    } else if keys.just_pressed(KeyCode::KeyY) {
+        commands.trigger(RedoMove);
+    }
+}
+
+/// Shakes a piece sideways for a moment, after its move was rejected.
+#[derive(Component)]
+struct Shake(Timer);
+
+fn shake_rejected(
+    time: Res<Time>,
+    mut shaking: Query<(Entity, &mut Shake, &mut Transform, &Square)>,
+    perspective: Res<Perspective>,
+    mut commands: Commands,
+) {
+    for (entity, mut shake, mut transform, square) in shaking.iter_mut() {
+        shake.0.tick(time.delta());
+
+        *transform = square_to_transform(*square, 1.0, perspective.0);
+        if shake.0.finished() {
+            commands.entity(entity).remove::<Shake>();
+            continue;
+        }
+        transform.translation.x += (shake.0.elapsed_secs() * 60.0).sin() * PIECE_SPRITE_SIZE * 0.08;
+    }
+}
+
+fn game_controls(keys: Res<ButtonInput<KeyCode>>, board: Res<Board>, mut commands: Commands) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::KeyN) {
+        commands.trigger(NewGame);
+    } else if keys.just_pressed(KeyCode::KeyR) {
+        commands.trigger(Resign(board.side_to_move()));
+    } else if keys.just_pressed(KeyCode::KeyD) {
+        commands.trigger(AgreeDraw);
+    }
+}
+
+fn log_fen(keys: Res<ButtonInput<KeyCode>>, board: Res<Board>) {
+    // without Ctrl, F is typed into the move entry
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        && keys.just_pressed(KeyCode::KeyF)
+    {
+        info!("{}", board.to_fen());
+    }
+}
+
+/// The square a board square sprite stands for.
+#[derive(Component, Clone, Copy)]
+struct BoardSquare(Square);
+
 const PIECE_SPRITE_SIZE: f32 = 128.0;
+const BOARD_CENTER: Vec2 = Vec2::new(-PIECE_SPRITE_SIZE * 0.5, -PIECE_SPRITE_SIZE * 0.5);
 
-#[derive(Resource)]
+#[derive(Resource, Clone, Debug)]
 struct PieceAssets {
     white_pawn: Handle<Image>,
     white_knight: Handle<Image>,
@@ -52,6 +378,25 @@ struct PieceAssets {
     black_king: Handle<Image>,
 }
 impl PieceAssets {
+    /// Loads the twelve images of a piece set, `load` being given each file name, like
+    /// `white_pawn.png`.
+    fn load(mut load: impl FnMut(&str) -> Handle<Image>) -> Self {
+        Self {
+            white_pawn: load("white_pawn.png"),
+            white_knight: load("white_knight.png"),
+            white_bishop: load("white_bishop.png"),
+            white_rook: load("white_rook.png"),
+            white_queen: load("white_queen.png"),
+            white_king: load("white_king.png"),
+            black_pawn: load("black_pawn.png"),
+            black_knight: load("black_knight.png"),
+            black_bishop: load("black_bishop.png"),
+            black_rook: load("black_rook.png"),
+            black_queen: load("black_queen.png"),
+            black_king: load("black_king.png"),
+        }
+    }
+
     fn get_image(&self, piece: chess_plugin::Piece, color: chess_plugin::Color) -> Handle<Image> {
         match piece {
             chess_plugin::Piece::Pawn => match color {
@@ -83,34 +428,9 @@ impl PieceAssets {
 }
 
 fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let white_pawn = asset_server.load("white_pawn.png");
-    let white_knight = asset_server.load("white_knight.png");
-    let white_bishop = asset_server.load("white_bishop.png");
-    let white_rook = asset_server.load("white_rook.png");
-    let white_queen = asset_server.load("white_queen.png");
-    let white_king = asset_server.load("white_king.png");
-
-    let black_pawn = asset_server.load("black_pawn.png");
-    let black_knight = asset_server.load("black_knight.png");
-    let black_bishop = asset_server.load("black_bishop.png");
-    let black_rook = asset_server.load("black_rook.png");
-    let black_queen = asset_server.load("black_queen.png");
-    let black_king = asset_server.load("black_king.png");
-
-    let piece_assets = PieceAssets {
-        white_pawn,
-        white_knight,
-        white_bishop,
-        white_rook,
-        white_queen,
-        white_king,
-        black_pawn,
-        black_knight,
-        black_bishop,
-        black_rook,
-        black_queen,
-        black_king,
-    };
+    // the default theme's pieces, there to draw with until the theme itself is loaded
+    let piece_assets =
+        PieceAssets::load(|name| asset_server.load(format!("pieces/classic/{name}")));
     commands.insert_resource(piece_assets);
 }
 
@@ -124,26 +444,27 @@ fn spawn_promotion_picker_piece<R: Relationship>(
     picker_index: usize,
     picker_piece: Piece,
     picker_color: chess_plugin::Color,
+    direction: f32,
 ) {
     commands
         .spawn((
+            PromotionPickerSlot(picker_index),
             Pickable::default(),
             Sprite::from_image(piece_assets.get_image(picker_piece, picker_color)),
-            Transform::from_xyz(
-                0.0,
-                // TODO: I don't like using "as". There should be a way to convert from usize to f32 cleanly, even if it implies returing a Result
-                PIECE_SPRITE_SIZE * 1.5 - PIECE_SPRITE_SIZE * picker_index as f32,
-                4.0,
-            ),
+            Transform::from_xyz(0.0, promotion_slot_y(picker_index, direction), 4.0),
             OnHover(CursorIcon::System(SystemCursorIcon::Pointer), 0),
         ))
         .observe(
             move |event: Trigger<Pointer<Click>>,
                   parents: Query<&ChildOf>,
                   mut commands: Commands| {
-                commands.trigger(MoveRequest {
+                let MoveRequest::Move { from, to, .. } = mv else {
+                    return Ok(());
+                };
+                commands.trigger(MoveRequest::Move {
+                    from,
+                    to,
                     promotion: Some(picker_piece),
-                    ..mv
                 });
 
                 commands
@@ -161,9 +482,33 @@ fn spawn_promotion_picker_piece<R: Relationship>(
         );
 }
 
+/// The pieces to promote to, in a column starting on the promotion square.
+#[derive(Component)]
+struct PromotionPicker(MoveRequest);
+
+#[derive(Component)]
+struct PromotionPickerSlot(usize);
+
+/// Where the picker for a promotion on `to` goes, and which way it extends from there: down from
+/// the top half of the screen, up from the bottom half.
+fn promotion_picker_layout(to: Square, perspective: chess_plugin::Color) -> (Vec2, f32) {
+    let square = square_to_xy(to, perspective);
+    let direction = if square.y > BOARD_CENTER.y { -1.0 } else { 1.0 };
+    (
+        square + Vec2::new(0.0, direction * PIECE_SPRITE_SIZE * 1.5),
+        direction,
+    )
+}
+
+fn promotion_slot_y(index: usize, direction: f32) -> f32 {
+    // TODO: I don't like using "as". There should be a way to convert from usize to f32 cleanly, even if it implies returing a Result
+    -direction * (PIECE_SPRITE_SIZE * 1.5 - PIECE_SPRITE_SIZE * index as f32)
+}
+
 fn spawn_promotion_picker(
     piece_assets: &PieceAssets,
     board: &Board,
+    perspective: chess_plugin::Color,
     moved_piece: Entity,
     visibility: Query<&Visibility>,
     commands: &mut Commands,
@@ -171,7 +516,7 @@ fn spawn_promotion_picker(
 ) {
     let color = board.side_to_move();
 
-    let Vec2 { x, y } = square_to_xy(mv.to);
+    let (Vec2 { x, y }, direction) = promotion_picker_layout(mv.to(), perspective);
 
     let current_visibility = visibility.get(moved_piece).ok().copied();
     commands.entity(moved_piece).insert(Visibility::Hidden);
@@ -183,7 +528,8 @@ fn spawn_promotion_picker(
                 Color::srgb(0.5, 0.5, 0.5),
                 Vec2::new(PIECE_SPRITE_SIZE, PIECE_SPRITE_SIZE * 4.0),
             ),
-            Transform::from_xyz(x, y - PIECE_SPRITE_SIZE * 1.5, 3.0),
+            Transform::from_xyz(x, y, 3.0),
+            PromotionPicker(mv),
         ))
         .with_children(|commands| {
             spawn_promotion_picker_piece(
@@ -195,6 +541,7 @@ fn spawn_promotion_picker(
                 0,
                 Piece::Queen,
                 color,
+                direction,
             );
             spawn_promotion_picker_piece(
                 commands,
@@ -205,6 +552,7 @@ fn spawn_promotion_picker(
                 1,
                 Piece::Rook,
                 color,
+                direction,
             );
             spawn_promotion_picker_piece(
                 commands,
@@ -215,6 +563,7 @@ fn spawn_promotion_picker(
                 2,
                 Piece::Knight,
                 color,
+                direction,
             );
             spawn_promotion_picker_piece(
                 commands,
@@ -225,19 +574,96 @@ fn spawn_promotion_picker(
                 3,
                 Piece::Bishop,
                 color,
+                direction,
             );
         });
 }
 
-fn setup(mut commands: Commands) {
-    commands.spawn(Camera2d);
+/// Shows the remaining time of one side's [`ChessClock`].
+#[derive(Component)]
+struct ClockDisplay(chess_plugin::Color);
 
-    for square in ALL_SQUARES {
-        let file = square.file();
-        let rank = square.rank();
-        let x = (u8::from(file) as f32) * PIECE_SPRITE_SIZE - PIECE_SPRITE_SIZE * 4.0;
-        let y = (u8::from(rank) as f32) * PIECE_SPRITE_SIZE - PIECE_SPRITE_SIZE * 4.0;
+fn update_clock_displays(
+    clock: Option<Res<ChessClock>>,
+    board: Res<Board>,
+    mut displays: Query<(&ClockDisplay, &mut Text2d, &mut TextColor)>,
+) {
+    let Some(clock) = clock else {
+        return;
+    };
+
+    for (display, mut text, mut text_color) in displays.iter_mut() {
+        text.0 = format_clock(clock.remaining(display.0));
+        text_color.0 = if clock.is_running() && board.side_to_move() == display.0 {
+            Color::WHITE
+        } else {
+            Color::srgb(0.5, 0.5, 0.5)
+        };
+    }
+}
+
+/// Next to the board, on the side of the player whose clock it is.
+fn clock_position(color: chess_plugin::Color, perspective: chess_plugin::Color) -> Vec2 {
+    let y = if color == perspective {
+        -PIECE_SPRITE_SIZE * 3.5
+    } else {
+        PIECE_SPRITE_SIZE * 3.5
+    };
+    BOARD_CENTER + Vec2::new(PIECE_SPRITE_SIZE * 5.5, y)
+}
+
+fn format_clock(remaining: Duration) -> String {
+    let seconds = remaining.as_secs();
+    if seconds >= 60 * 60 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else if seconds >= 10 {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    } else {
+        // tenths matter when time is short
+        format!("0:{:04.1}", remaining.as_secs_f32())
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    clock: Option<Res<ChessClock>>,
+    perspective: Res<Perspective>,
+    square_colors: Res<SquareColors>,
+) {
+    commands.spawn((
+        Camera2d,
+        // keep the board, and what is drawn around it, in view
+        Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::AutoMin {
+                min_width: PIECE_SPRITE_SIZE * 14.0,
+                min_height: PIECE_SPRITE_SIZE * 9.0,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+        Transform::from_translation(BOARD_CENTER.extend(0.0)),
+    ));
+
+    if clock.is_some() {
+        for color in [chess_plugin::Color::White, chess_plugin::Color::Black] {
+            commands.spawn((
+                ClockDisplay(color),
+                Text2d::default(),
+                TextFont {
+                    font_size: 64.0,
+                    ..Default::default()
+                },
+                TextColor::default(),
+                Transform::from_translation(clock_position(color, perspective.0).extend(0.0)),
+            ));
+        }
+    }
 
+    for square in ALL_SQUARES {
         commands
             .spawn((
                 Pickable {
@@ -245,12 +671,19 @@ fn setup(mut commands: Commands) {
                     ..Default::default()
                 },
                 square,
-                square_to_transform(square, 1.0),
+                square_to_transform(square, 1.0, perspective.0),
                 OnHover(CursorIcon::System(SystemCursorIcon::Grab), 0),
                 OnClick(CursorIcon::System(SystemCursorIcon::Grabbing), 1),
             ))
             .observe(
-                |pressed: Trigger<Pointer<Pressed>>, mut transforms: Query<&mut Transform>| {
+                |pressed: Trigger<Pointer<Pressed>>,
+                 mut transforms: Query<&mut Transform>,
+                 input_mode: Res<InputMode>,
+                 animations: Query<(), With<PieceAnimation>>,
+                 view: Res<HistoryView>| {
+                    if !input_mode.drag() || !animations.is_empty() || !view.is_live() {
+                        return Ok(());
+                    }
                     let mut transform = transforms.get_mut(pressed.target())?;
                     let position = pressed.hit.position.ok_or("need hit position")?;
                     transform.translation.x = position.x;
@@ -261,7 +694,14 @@ fn setup(mut commands: Commands) {
                 },
             )
             .observe(
-                |dragged: Trigger<Pointer<Drag>>, mut transforms: Query<&mut Transform>| {
+                |dragged: Trigger<Pointer<Drag>>,
+                 mut transforms: Query<&mut Transform>,
+                 input_mode: Res<InputMode>,
+                 animations: Query<(), With<PieceAnimation>>,
+                 view: Res<HistoryView>| {
+                    if !input_mode.drag() || !animations.is_empty() || !view.is_live() {
+                        return Ok(());
+                    }
                     let mut transform = transforms.get_mut(dragged.target())?;
                     let delta = dragged.delta;
                     transform.translation.x += delta.x;
@@ -272,16 +712,17 @@ fn setup(mut commands: Commands) {
             )
             .observe(
                 |pressed: Trigger<Pointer<Released>>,
-                 mut transforms: Query<(&mut Transform, &Square)>| {
+                 mut transforms: Query<(&mut Transform, &Square)>,
+                 perspective: Res<Perspective>| {
                     let (mut transform, square) = transforms.get_mut(pressed.target())?;
 
-                    *transform = square_to_transform(*square, 1.0);
+                    *transform = square_to_transform(*square, 1.0, perspective.0);
 
                     Ok(())
                 },
             )
             .observe(
-                |trigger: Trigger<OnAdd, ColoredPiece>,
+                |trigger: Trigger<OnInsert, ColoredPiece>,
                  pieces: Query<&ColoredPiece>,
                  mut commands: Commands,
                  piece_assets: Res<PieceAssets>| {
@@ -304,42 +745,69 @@ fn setup(mut commands: Commands) {
         // spawn the square
         commands
             .spawn((
+                BoardSquare(square),
                 Pickable::default(),
                 Sprite::from_color(
-                    if (u8::from(rank) + u8::from(file)) % 2 == 0 {
-                        Color::srgb(0.1, 0.1, 0.1) // dark color
-                    } else {
-                        Color::srgb(0.9, 0.9, 0.9) // light color
-                    },
+                    square_colors.of(square),
                     Vec2::new(PIECE_SPRITE_SIZE, PIECE_SPRITE_SIZE),
                 ),
-                Transform::from_xyz(x, y, 0.0),
+                square_to_transform(square, 0.0, perspective.0),
             ))
             .observe(
                 move |drop: Trigger<Pointer<DragDrop>>,
                       mut squares: Query<&Square, With<ColoredPiece>>,
+                      pocket_pieces: Query<&PocketPiece>,
                       visibility: Query<&Visibility>,
                       mut commands: Commands,
                       // this doesn't need to be here if needs_promotion is moved into a different system and triggered with an event
                       piece_assets: Res<PieceAssets>,
-                      board: Res<Board>| {
+                      board: Res<Board>,
+                      input_mode: Res<InputMode>,
+                      perspective: Res<Perspective>,
+                      animations: Query<(), With<PieceAnimation>>,
+                      view: Res<HistoryView>,
+                      mut dropped: ResMut<DroppedPiece>| {
+                    if !input_mode.drag() || !animations.is_empty() || !view.is_live() {
+                        return Ok(());
+                    }
+                    if let Ok(pocket_piece) = pocket_pieces.get(drop.dropped) {
+                        // a drop is always from the pocket of the side to move
+                        if pocket_piece.color != board.side_to_move() {
+                            return Ok(());
+                        }
+                        commands.trigger(MoveRequest::Drop {
+                            piece: pocket_piece.piece,
+                            to: square,
+                        });
+                        return Ok(());
+                    }
                     let Ok(from) = squares.get_mut(drop.dropped) else {
                         // if the dropped entity is not a piece, do nothing
                         return Ok(());
                     };
 
                     let to = square;
+                    if *from == to {
+                        // put back down where it was picked up
+                        return Ok(());
+                    }
 
-                    let mv = MoveRequest {
+                    let mv = MoveRequest::Move {
                         from: *from,
                         to,
                         promotion: None,
                     };
+                    // a king dropped onto its own rook castles, and isn't where it ends up yet:
+                    // let it slide there from where it was picked up
+                    if board.color_on(to) != board.color_on(*from) {
+                        dropped.0 = Some(*from);
+                    }
 
                     if board.needs_promotion(mv) {
                         spawn_promotion_picker(
                             &piece_assets,
                             &board,
+                            perspective.0,
                             drop.dropped,
                             visibility,
                             &mut commands,
@@ -357,27 +825,17 @@ fn setup(mut commands: Commands) {
     }
 }
 
-fn square_to_transform(square: Square, z: f32) -> Transform {
-    let file = square.file();
-    let rank = square.rank();
-    let x = (u8::from(file) as f32) * PIECE_SPRITE_SIZE - PIECE_SPRITE_SIZE * 4.0;
-    let y = (u8::from(rank) as f32) * PIECE_SPRITE_SIZE - PIECE_SPRITE_SIZE * 4.0;
-    Transform::from_xyz(x, y, z)
+fn square_to_transform(square: Square, z: f32, perspective: chess_plugin::Color) -> Transform {
+    Transform::from_translation(square_to_xy(square, perspective).extend(z))
 }
 
-fn square_to_xy(square: Square) -> Vec2 {
-    let file = square.file();
-    let rank = square.rank();
-    let x = (u8::from(file) as f32) * PIECE_SPRITE_SIZE - PIECE_SPRITE_SIZE * 4.0;
-    let y = (u8::from(rank) as f32) * PIECE_SPRITE_SIZE - PIECE_SPRITE_SIZE * 4.0;
+/// Where `square` is drawn, with `perspective`'s side at the bottom of the screen.
+fn square_to_xy(square: Square, perspective: chess_plugin::Color) -> Vec2 {
+    let (file, rank) = match perspective {
+        chess_plugin::Color::White => (u8::from(square.file()), u8::from(square.rank())),
+        chess_plugin::Color::Black => (7 - u8::from(square.file()), 7 - u8::from(square.rank())),
+    };
+    let x = (file as f32) * PIECE_SPRITE_SIZE - PIECE_SPRITE_SIZE * 4.0;
+    let y = (rank as f32) * PIECE_SPRITE_SIZE - PIECE_SPRITE_SIZE * 4.0;
     Vec2::new(x, y)
 }
-
-#[cfg(test)]
-mod tests {
-    #[test]
-    #[expect(clippy::assertions_on_constants)]
-    fn test() {
-        assert!(true);
-    }
-}
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use bevy_game_2::chess_plugin::{Board, ColoredPiece, Square};
+
+use crate::{
+    PIECE_SPRITE_SIZE, animation::PieceAnimation, highlights::HighlightColors,
+    move_list::HistoryView, orientation::Perspective, square_to_xy,
+};
+
+/// Marks where the piece being held may go, removed once it is let go.
+#[derive(Component)]
+pub struct MoveHint;
+
+#[derive(Resource)]
+struct MoveHintAssets {
+    dot: Handle<Mesh>,
+    ring: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+pub struct MoveHintsPlugin;
+
+impl Plugin for MoveHintsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_move_hint_assets)
+            .add_systems(
+                Update,
+                restyle_move_hints.run_if(resource_changed::<HighlightColors>),
+            )
+            .add_observer(show_move_hints)
+            .add_observer(clear_move_hints);
+    }
+}
+
+fn load_move_hint_assets(
+    colors: Res<HighlightColors>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+) {
+    commands.insert_resource(MoveHintAssets {
+        // quiet moves get a dot in the middle of the square, captures a ring around the piece
+        dot: meshes.add(Circle::new(PIECE_SPRITE_SIZE * 0.16)),
+        ring: meshes.add(Annulus::new(
+            PIECE_SPRITE_SIZE * 0.42,
+            PIECE_SPRITE_SIZE * 0.5,
+        )),
+        material: materials.add(colors.move_hint),
+    });
+}
+
+fn restyle_move_hints(
+    colors: Res<HighlightColors>,
+    assets: Option<Res<MoveHintAssets>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+    if let Some(material) = materials.get_mut(&assets.material) {
+        material.color = colors.move_hint;
+    }
+}
+
+#[expect(clippy::too_many_arguments)]
+fn show_move_hints(
+    pressed: Trigger<Pointer<Pressed>>,
+    pieces: Query<&Square, With<ColoredPiece>>,
+    board: Res<Board>,
+    assets: Res<MoveHintAssets>,
+    perspective: Res<Perspective>,
+    animations: Query<(), With<PieceAnimation>>,
+    view: Res<HistoryView>,
+    mut commands: Commands,
+) {
+    let Ok(from) = pieces.get(pressed.target()) else {
+        return;
+    };
+    if !animations.is_empty() || !view.is_live() {
+        return;
+    }
+
+    let mut moves = board.legal_moves_from(*from);
+    // a promotion is offered once for each piece, but only needs one hint
+    moves.dedup_by_key(|mv| mv.to());
+
+    for mv in moves {
+        let mesh = if board.is_capture(mv) {
+            assets.ring.clone()
+        } else {
+            assets.dot.clone()
+        };
+        commands.spawn((
+            MoveHint,
+            Mesh2d(mesh),
+            MeshMaterial2d(assets.material.clone()),
+            // above the squares, below the pieces
+            Transform::from_translation(square_to_xy(mv.to(), perspective.0).extend(0.5)),
+            // drops have to reach the square underneath
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+fn clear_move_hints(
+    _: Trigger<Pointer<Released>>,
+    hints: Query<Entity, With<MoveHint>>,
+    mut commands: Commands,
+) {
+    for hint in hints.iter() {
+        commands.entity(hint).despawn();
+    }
+}
@@ -0,0 +1,193 @@
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+use bevy_game_2::chess_plugin::{Board, GameResult};
+
+use crate::{
+    animation::PieceAnimation,
+    move_list::{HistoryView, PANEL_WIDTH},
+};
+
+/// How much room the move entry takes at the bottom of the side panel.
+pub const ENTRY_HEIGHT: f32 = 64.0;
+const MAX_LENGTH: usize = 12;
+/// how many completions are shown at once
+const SHOWN_COMPLETIONS: usize = 6;
+
+/// The move being typed, in SAN or UCI. Enter plays it, Tab completes it, Escape clears it.
+#[derive(Resource, Default)]
+pub struct MoveEntry {
+    pub text: String,
+    /// why the last entered move wasn't played
+    pub error: Option<String>,
+}
+
+#[derive(Component)]
+struct MoveEntryText;
+
+#[derive(Component)]
+struct MoveEntryHint;
+
+pub struct MoveInputPlugin;
+
+impl Plugin for MoveInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MoveEntry>()
+            .add_systems(Startup, spawn_move_entry)
+            .add_systems(
+                Update,
+                (
+                    type_move,
+                    show_move_entry
+                        .run_if(resource_changed::<MoveEntry>.or(resource_changed::<Board>)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn spawn_move_entry(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            width: Val::Px(PANEL_WIDTH),
+            height: Val::Px(ENTRY_HEIGHT),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(8.0)),
+            row_gap: Val::Px(4.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        children![
+            (
+                MoveEntryText,
+                Text::default(),
+                TextFont {
+                    font_size: 18.0,
+                    ..Default::default()
+                },
+                TextColor(Color::srgb(0.95, 0.95, 0.95)),
+            ),
+            (
+                MoveEntryHint,
+                Text::default(),
+                TextFont {
+                    font_size: 14.0,
+                    ..Default::default()
+                },
+                TextColor::default(),
+            ),
+        ],
+    ));
+}
+
+#[expect(clippy::too_many_arguments)]
+fn type_move(
+    mut keyboard: EventReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    board: Res<Board>,
+    result: Res<GameResult>,
+    view: Res<HistoryView>,
+    animations: Query<(), With<PieceAnimation>>,
+    mut entry: ResMut<MoveEntry>,
+    mut commands: Commands,
+) {
+    // shortcuts are left alone
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        keyboard.clear();
+        return;
+    }
+
+    for input in keyboard.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &input.logical_key {
+            Key::Character(typed) => {
+                if entry.text.len() + typed.len() <= MAX_LENGTH {
+                    entry.text.push_str(typed);
+                    entry.error = None;
+                }
+            }
+            Key::Backspace => {
+                entry.text.pop();
+                entry.error = None;
+            }
+            Key::Escape => {
+                *entry = MoveEntry::default();
+            }
+            Key::Tab => {
+                if let Some(completion) = board.complete_move_entry(&entry.text).into_iter().next()
+                {
+                    entry.text = completion;
+                }
+            }
+            Key::Enter => {
+                if entry.text.trim().is_empty() {
+                    continue;
+                }
+                if !view.is_live() {
+                    entry.error = Some("go back to the game first".to_string());
+                    continue;
+                }
+                if result.is_over() {
+                    entry.error = Some("the game is over".to_string());
+                    continue;
+                }
+                if !animations.is_empty() {
+                    // the move is kept, to be entered again in a moment
+                    continue;
+                }
+
+                match board.parse_move_entry(&entry.text) {
+                    Ok(mv) => {
+                        *entry = MoveEntry::default();
+                        commands.trigger(mv);
+                    }
+                    Err(error) => entry.error = Some(error.to_string()),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn show_move_entry(
+    entry: Res<MoveEntry>,
+    board: Res<Board>,
+    mut text: Query<&mut Text, (With<MoveEntryText>, Without<MoveEntryHint>)>,
+    mut hint: Query<(&mut Text, &mut TextColor), With<MoveEntryHint>>,
+) -> Result {
+    text.single_mut()?.0 = format!("> {}_", entry.text);
+
+    let (mut hint, mut color) = hint.single_mut()?;
+    if let Some(error) = &entry.error {
+        hint.0 = error.clone();
+        color.0 = Color::srgb(0.95, 0.35, 0.3);
+    } else if entry.text.is_empty() {
+        hint.0 = "type a move, like e4 or g1f3".to_string();
+        color.0 = Color::srgba(0.85, 0.85, 0.85, 0.5);
+    } else {
+        let completions = board.complete_move_entry(&entry.text);
+        let mut shown = completions
+            .iter()
+            .take(SHOWN_COMPLETIONS)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("  ");
+        if completions.len() > SHOWN_COMPLETIONS {
+            shown.push_str("  …");
+        }
+        hint.0 = shown;
+        color.0 = Color::srgb(0.85, 0.85, 0.85);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,277 @@
+use bevy::{input::mouse::MouseScrollUnit, prelude::*};
+use bevy_game_2::chess_plugin::{self, Board, ColoredPiece, MoveHistory, Square};
+
+use crate::move_input::ENTRY_HEIGHT;
+
+pub const PANEL_WIDTH: f32 = 220.0;
+const ROW_HEIGHT: f32 = 24.0;
+const TEXT_COLOR: Color = Color::srgb(0.85, 0.85, 0.85);
+const CURRENT_MOVE_COLOR: Color = Color::srgba(0.95, 0.8, 0.2, 0.35);
+
+/// Which position the board shows: the game as it is, or how it was a number of moves (plies)
+/// in. Pieces can't be moved while looking back, and a new move brings the board back to the
+/// game.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HistoryView(Option<usize>);
+
+impl HistoryView {
+    pub fn is_live(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Shows the position after `plies` moves, the live one if that is all of them.
+    fn at(plies: usize, history: &MoveHistory) -> Self {
+        Self((plies < history.played().len()).then_some(plies))
+    }
+
+    fn plies(&self, history: &MoveHistory) -> usize {
+        self.0.unwrap_or(history.played().len())
+    }
+}
+
+#[derive(Component)]
+struct MoveList;
+
+/// A move in the list, the `n`th one played, from 0.
+#[derive(Component)]
+struct MoveListEntry(usize);
+
+pub struct MoveListPlugin;
+
+impl Plugin for MoveListPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HistoryView>()
+            .add_systems(Startup, spawn_move_list)
+            .add_systems(
+                Update,
+                (
+                    back_to_game_on_move.run_if(resource_changed::<Board>),
+                    step_through_history,
+                    (show_viewed_position, list_moves).run_if(
+                        resource_changed::<HistoryView>.or(resource_changed::<MoveHistory>),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn spawn_move_list(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                // the move entry sits below
+                bottom: Val::Px(ENTRY_HEIGHT),
+                width: Val::Px(PANEL_WIDTH),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                overflow: Overflow::scroll_y(),
+                ..Default::default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            MoveList,
+        ))
+        .observe(
+            |scroll: Trigger<Pointer<Scroll>>,
+             mut lists: Query<&mut ScrollPosition, With<MoveList>>| {
+                let Ok(mut position) = lists.get_mut(scroll.target()) else {
+                    return;
+                };
+                let lines = match scroll.unit {
+                    MouseScrollUnit::Line => scroll.y * ROW_HEIGHT,
+                    MouseScrollUnit::Pixel => scroll.y,
+                };
+                position.offset_y = (position.offset_y - lines).max(0.0);
+            },
+        );
+}
+
+fn back_to_game_on_move(mut view: ResMut<HistoryView>) {
+    view.set_if_neq(HistoryView::default());
+}
+
+/// Left and right step through the moves, Home goes to the start and End back to the game.
+fn step_through_history(
+    keys: Res<ButtonInput<KeyCode>>,
+    history: Res<MoveHistory>,
+    mut view: ResMut<HistoryView>,
+) {
+    let plies = view.plies(&history);
+    let wanted = if keys.just_pressed(KeyCode::ArrowLeft) {
+        plies.saturating_sub(1)
+    } else if keys.just_pressed(KeyCode::ArrowRight) {
+        plies + 1
+    } else if keys.just_pressed(KeyCode::Home) {
+        0
+    } else if keys.just_pressed(KeyCode::End) {
+        history.played().len()
+    } else {
+        return;
+    };
+    view.set_if_neq(HistoryView::at(wanted, &history));
+}
+
+// the pieces are put where they stood, without touching the board: the live position comes back
+// the same way
+fn show_viewed_position(
+    view: Res<HistoryView>,
+    history: Res<MoveHistory>,
+    board: Res<Board>,
+    squares: Query<(Entity, &Square, Option<&ColoredPiece>)>,
+    mut commands: Commands,
+) {
+    let shown = view
+        .0
+        .and_then(|plies| history.played().get(plies))
+        .map_or(&*board, |played| &played.before);
+
+    for (entity, square, current) in squares.iter() {
+        let wanted = shown.piece_on(*square).zip(shown.color_on(*square));
+        let wanted = wanted.map(|(piece, color)| ColoredPiece { piece, color });
+        if current.copied() == wanted {
+            continue;
+        }
+
+        match wanted {
+            Some(piece) => {
+                commands.entity(entity).insert(piece);
+            }
+            None => {
+                commands.entity(entity).remove::<ColoredPiece>();
+            }
+        }
+    }
+}
+
+fn list_moves(
+    view: Res<HistoryView>,
+    history: Res<MoveHistory>,
+    mut lists: Query<(Entity, &mut ScrollPosition), With<MoveList>>,
+    mut commands: Commands,
+) {
+    let Ok((list, mut scroll)) = lists.single_mut() else {
+        return;
+    };
+    let shown = view.plies(&history);
+
+    commands
+        .entity(list)
+        .despawn_related::<Children>()
+        .with_children(|list| {
+            if !view.is_live() {
+                list.spawn((
+                    Button,
+                    Node {
+                        height: Val::Px(ROW_HEIGHT),
+                        margin: UiRect::bottom(Val::Px(4.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    BackgroundColor(CURRENT_MOVE_COLOR),
+                    children![(
+                        Text::new("Back to game (End)"),
+                        text_font(),
+                        TextColor(TEXT_COLOR)
+                    )],
+                ))
+                .observe(
+                    |_: Trigger<Pointer<Click>>, mut view: ResMut<HistoryView>| {
+                        view.set_if_neq(HistoryView::default());
+                    },
+                );
+            }
+
+            let mut row = None;
+            for (index, played) in history.played().iter().enumerate() {
+                let white = played.before.side_to_move() == chess_plugin::Color::White;
+                // a new row on every white move, and on a first move made by black
+                if white || row.is_none() {
+                    let number = played.before.fullmove_number();
+                    let entity = list
+                        .spawn((
+                            Node {
+                                height: Val::Px(ROW_HEIGHT),
+                                align_items: AlignItems::Center,
+                                flex_shrink: 0.0,
+                                ..Default::default()
+                            },
+                            children![(
+                                Node {
+                                    width: Val::Px(40.0),
+                                    ..Default::default()
+                                },
+                                Text::new(format!("{number}.")),
+                                text_font(),
+                                TextColor(TEXT_COLOR.with_alpha(0.6)),
+                            )],
+                        ))
+                        .id();
+                    if !white {
+                        list.commands().spawn((
+                            ChildOf(entity),
+                            Node {
+                                width: Val::Px(80.0),
+                                ..Default::default()
+                            },
+                            Text::new("..."),
+                            text_font(),
+                            TextColor(TEXT_COLOR),
+                        ));
+                    }
+                    row = Some(entity);
+                }
+                let row = row.expect("set above");
+
+                // the move that led to the shown position stands out
+                let current = index + 1 == shown;
+                list.commands()
+                    .spawn((
+                        ChildOf(row),
+                        MoveListEntry(index),
+                        Button,
+                        Node {
+                            width: Val::Px(80.0),
+                            padding: UiRect::horizontal(Val::Px(4.0)),
+                            ..Default::default()
+                        },
+                        BackgroundColor(if current {
+                            CURRENT_MOVE_COLOR
+                        } else {
+                            Color::NONE
+                        }),
+                        Text::new(played.before.san(played.mv)),
+                        text_font(),
+                        TextColor(TEXT_COLOR),
+                    ))
+                    .observe(view_clicked_move);
+            }
+        });
+
+    if view.is_live() {
+        // keep the latest moves in sight, the layout clamps this to the end of the list
+        scroll.offset_y = f32::MAX;
+    }
+}
+
+fn view_clicked_move(
+    click: Trigger<Pointer<Click>>,
+    entries: Query<&MoveListEntry>,
+    history: Res<MoveHistory>,
+    mut view: ResMut<HistoryView>,
+) {
+    let Ok(entry) = entries.get(click.target()) else {
+        return;
+    };
+    view.set_if_neq(HistoryView::at(entry.0 + 1, &history));
+}
+
+fn text_font() -> TextFont {
+    TextFont {
+        font_size: 16.0,
+        ..Default::default()
+    }
+}
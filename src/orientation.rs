@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use bevy_game_2::chess_plugin::{self, Board, Square};
+
+use crate::{
+    BoardSquare, ClockDisplay, PromotionPicker, PromotionPickerSlot, clock_position,
+    promotion_picker_layout, promotion_slot_y, square_to_xy,
+};
+
+/// Which side of the board is at the bottom of the screen. Ctrl+O cycles through them.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BoardOrientation {
+    #[default]
+    White,
+    Black,
+    /// whoever's turn it is, for two players sharing a screen
+    SideToMove,
+}
+
+/// The side currently at the bottom of the screen, as [`BoardOrientation`] works out.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq, Deref)]
+pub struct Perspective(pub chess_plugin::Color);
+
+impl Default for Perspective {
+    fn default() -> Self {
+        Self(chess_plugin::Color::White)
+    }
+}
+
+pub struct OrientationPlugin;
+
+impl Plugin for OrientationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BoardOrientation>()
+            .init_resource::<Perspective>()
+            .add_systems(
+                Update,
+                (
+                    cycle_orientation,
+                    update_perspective
+                        .run_if(resource_changed::<BoardOrientation>.or(resource_changed::<Board>)),
+                    (
+                        reposition_squares,
+                        reposition_pieces,
+                        reposition_clocks,
+                        reposition_promotion_pickers,
+                    )
+                        .run_if(resource_changed::<Perspective>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn cycle_orientation(keys: Res<ButtonInput<KeyCode>>, mut orientation: ResMut<BoardOrientation>) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.just_pressed(KeyCode::KeyO)
+    {
+        return;
+    }
+
+    *orientation = match *orientation {
+        BoardOrientation::White => BoardOrientation::Black,
+        BoardOrientation::Black => BoardOrientation::SideToMove,
+        BoardOrientation::SideToMove => BoardOrientation::White,
+    };
+}
+
+fn update_perspective(
+    orientation: Res<BoardOrientation>,
+    board: Res<Board>,
+    mut perspective: ResMut<Perspective>,
+) {
+    let bottom = match *orientation {
+        BoardOrientation::White => chess_plugin::Color::White,
+        BoardOrientation::Black => chess_plugin::Color::Black,
+        BoardOrientation::SideToMove => board.side_to_move(),
+    };
+    perspective.set_if_neq(Perspective(bottom));
+}
+
+fn reposition_squares(
+    perspective: Res<Perspective>,
+    mut squares: Query<(&BoardSquare, &mut Transform)>,
+) {
+    for (square, mut transform) in squares.iter_mut() {
+        let z = transform.translation.z;
+        transform.translation = square_to_xy(square.0, perspective.0).extend(z);
+    }
+}
+
+fn reposition_pieces(perspective: Res<Perspective>, mut pieces: Query<(&Square, &mut Transform)>) {
+    for (square, mut transform) in pieces.iter_mut() {
+        let z = transform.translation.z;
+        transform.translation = square_to_xy(*square, perspective.0).extend(z);
+    }
+}
+
+fn reposition_clocks(
+    perspective: Res<Perspective>,
+    mut clocks: Query<(&ClockDisplay, &mut Transform)>,
+) {
+    for (clock, mut transform) in clocks.iter_mut() {
+        let z = transform.translation.z;
+        transform.translation = clock_position(clock.0, perspective.0).extend(z);
+    }
+}
+
+fn reposition_promotion_pickers(
+    perspective: Res<Perspective>,
+    mut pickers: Query<(&PromotionPicker, &mut Transform, &Children)>,
+    mut slots: Query<(&PromotionPickerSlot, &mut Transform), Without<PromotionPicker>>,
+) {
+    for (picker, mut transform, children) in pickers.iter_mut() {
+        let (position, direction) = promotion_picker_layout(picker.0.to(), perspective.0);
+        let z = transform.translation.z;
+        transform.translation = position.extend(z);
+
+        for child in children.iter() {
+            if let Ok((slot, mut transform)) = slots.get_mut(child) {
+                transform.translation.y = promotion_slot_y(slot.0, direction);
+            }
+        }
+    }
+}
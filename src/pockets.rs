@@ -0,0 +1,174 @@
+use bevy::{prelude::*, sprite::Anchor, window::SystemCursorIcon, winit::cursor::CursorIcon};
+use bevy_game_2::chess_plugin::{self, Board, Piece};
+
+use crate::{
+    BOARD_CENTER, PIECE_SPRITE_SIZE, PieceAssets,
+    animation::PieceAnimation,
+    click_to_move::InputMode,
+    cursor_style::{OnClick, OnHover},
+    move_list::HistoryView,
+    orientation::Perspective,
+};
+
+const ICON_SIZE: f32 = PIECE_SPRITE_SIZE * 0.45;
+const SLOT_STEP: f32 = ICON_SIZE + PIECE_SPRITE_SIZE * 0.05;
+
+/// Beside the board, the pieces a side can drop, in variants with pockets. They are dragged onto
+/// an empty square to be dropped there. Rebuilt whenever the board changes.
+#[derive(Component)]
+pub struct PocketTray(pub chess_plugin::Color);
+
+/// A piece in a [`PocketTray`], standing for all of its kind there.
+#[derive(Component, Clone, Copy)]
+pub struct PocketPiece {
+    pub piece: Piece,
+    pub color: chess_plugin::Color,
+}
+
+pub struct PocketsPlugin;
+
+impl Plugin for PocketsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_pocket_trays).add_systems(
+            Update,
+            update_pocket_trays.run_if(
+                resource_changed::<Board>
+                    .or(resource_changed::<Perspective>)
+                    .or(resource_changed::<PieceAssets>),
+            ),
+        );
+    }
+}
+
+/// Where the tray of `color` starts, where the captured pieces are shown otherwise.
+fn tray_position(color: chess_plugin::Color, perspective: chess_plugin::Color) -> Vec2 {
+    let y = if color == perspective {
+        -PIECE_SPRITE_SIZE * 2.5
+    } else {
+        PIECE_SPRITE_SIZE * 2.5
+    };
+    BOARD_CENTER + Vec2::new(PIECE_SPRITE_SIZE * 4.3 + ICON_SIZE * 0.5, y)
+}
+
+fn spawn_pocket_trays(mut commands: Commands) {
+    for color in [chess_plugin::Color::White, chess_plugin::Color::Black] {
+        commands.spawn((
+            PocketTray(color),
+            Transform::default(),
+            Visibility::default(),
+        ));
+    }
+}
+
+fn update_pocket_trays(
+    board: Res<Board>,
+    perspective: Res<Perspective>,
+    piece_assets: Res<PieceAssets>,
+    mut trays: Query<(Entity, &PocketTray, &mut Transform)>,
+    mut commands: Commands,
+) {
+    for (entity, tray, mut transform) in trays.iter_mut() {
+        transform.translation = tray_position(tray.0, perspective.0).extend(0.0);
+
+        commands.entity(entity).despawn_related::<Children>();
+        if !board.variant().has_pockets() {
+            continue;
+        }
+
+        let pocket = board
+            .pocket(tray.0)
+            .into_iter()
+            .filter(|(_, count)| *count > 0);
+        for (index, (piece, count)) in pocket.enumerate() {
+            let home = Vec3::new(index as f32 * SLOT_STEP, 0.0, 0.0);
+            commands
+                .spawn((
+                    ChildOf(entity),
+                    PocketPiece {
+                        piece,
+                        color: tray.0,
+                    },
+                    Sprite {
+                        image: piece_assets.get_image(piece, tray.0),
+                        custom_size: Some(Vec2::splat(ICON_SIZE)),
+                        ..Default::default()
+                    },
+                    Transform::from_translation(home),
+                    // the square under it needs to see where it is dropped
+                    Pickable {
+                        should_block_lower: false,
+                        ..Default::default()
+                    },
+                    OnHover(CursorIcon::System(SystemCursorIcon::Grab), 0),
+                    OnClick(CursorIcon::System(SystemCursorIcon::Grabbing), 1),
+                    children![(
+                        Text2d::new(format!("{count}")),
+                        TextFont {
+                            font_size: ICON_SIZE * 0.35,
+                            ..Default::default()
+                        },
+                        Anchor::BottomRight,
+                        Transform::from_xyz(ICON_SIZE * 0.5, -ICON_SIZE * 0.5, 0.1),
+                        Pickable::IGNORE,
+                    )],
+                ))
+                .observe(pick_up)
+                .observe(drag)
+                .observe(
+                    move |released: Trigger<Pointer<Released>>,
+                          mut transforms: Query<&mut Transform>| {
+                        let mut transform = transforms.get_mut(released.target())?;
+                        *transform = Transform::from_translation(home);
+
+                        Ok(())
+                    },
+                );
+        }
+    }
+}
+
+/// Whether pocket pieces can be picked up, as pieces on the board can.
+fn can_drag(
+    input_mode: &InputMode,
+    animations: &Query<(), With<PieceAnimation>>,
+    view: &HistoryView,
+) -> bool {
+    input_mode.drag() && animations.is_empty() && view.is_live()
+}
+
+fn pick_up(
+    pressed: Trigger<Pointer<Pressed>>,
+    mut transforms: Query<(&mut Transform, &ChildOf)>,
+    trays: Query<&GlobalTransform, With<PocketTray>>,
+    input_mode: Res<InputMode>,
+    animations: Query<(), With<PieceAnimation>>,
+    view: Res<HistoryView>,
+) -> Result {
+    if !can_drag(&input_mode, &animations, &view) {
+        return Ok(());
+    }
+    let (mut transform, tray) = transforms.get_mut(pressed.target())?;
+    let position = pressed.hit.position.ok_or("need hit position")?;
+    // the piece moves within its tray
+    let tray = trays.get(tray.parent())?.translation();
+    transform.translation = (position - tray).with_z(2.0);
+
+    Ok(())
+}
+
+fn drag(
+    dragged: Trigger<Pointer<Drag>>,
+    mut transforms: Query<&mut Transform>,
+    input_mode: Res<InputMode>,
+    animations: Query<(), With<PieceAnimation>>,
+    view: Res<HistoryView>,
+) -> Result {
+    if !can_drag(&input_mode, &animations, &view) {
+        return Ok(());
+    }
+    let mut transform = transforms.get_mut(dragged.target())?;
+    transform.translation.x += dragged.delta.x;
+    transform.translation.y -= dragged.delta.y;
+
+    Ok(())
+}
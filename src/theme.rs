@@ -0,0 +1,208 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    color::HexColorError,
+    prelude::*,
+};
+use bevy_game_2::chess_plugin::{ColoredPiece, Square};
+use derive_more::{Display, Error, From};
+use serde::Deserialize;
+
+use crate::{BoardSquare, PieceAssets, highlights::HighlightColors};
+
+/// The themes Ctrl+T cycles through, the first one being used at startup.
+const THEMES: [&str; 2] = ["themes/classic.theme.ron", "themes/wood.theme.ron"];
+
+/// How the board looks, loaded from a `.theme.ron` file: colors are hex strings and
+/// `piece_set` is the folder, under `assets`, holding the twelve piece images.
+#[derive(Asset, TypePath, Clone, Debug)]
+pub struct BoardTheme {
+    pub light_square: Color,
+    pub dark_square: Color,
+    pub highlights: HighlightColors,
+    pub pieces: PieceAssets,
+}
+
+/// Colors of the board squares, for themes to change.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct SquareColors {
+    pub light: Color,
+    pub dark: Color,
+}
+
+impl Default for SquareColors {
+    fn default() -> Self {
+        Self {
+            light: Color::srgb(0.9, 0.9, 0.9),
+            dark: Color::srgb(0.1, 0.1, 0.1),
+        }
+    }
+}
+
+impl SquareColors {
+    pub fn of(&self, square: Square) -> Color {
+        if (u8::from(square.rank()) + u8::from(square.file())) % 2 == 0 {
+            self.dark
+        } else {
+            self.light
+        }
+    }
+}
+
+/// The theme in use. It is applied once loaded, and again whenever its file changes.
+#[derive(Resource, Clone, Debug, Deref)]
+pub struct CurrentTheme(pub Handle<BoardTheme>);
+
+/// Switches to another theme.
+#[derive(Event, Clone, Debug)]
+pub struct SetTheme(pub Handle<BoardTheme>);
+
+#[derive(Resource)]
+struct LoadedThemes(Vec<Handle<BoardTheme>>);
+
+#[derive(Deserialize)]
+struct BoardThemeFile {
+    light_square: String,
+    dark_square: String,
+    last_move: String,
+    check: String,
+    move_hint: String,
+    selection: String,
+    piece_set: String,
+}
+
+#[derive(Debug, Display, Error, From)]
+pub enum BoardThemeError {
+    #[display("could not read the theme: {_0}")]
+    Io(std::io::Error),
+    #[display("invalid theme: {_0}")]
+    Ron(ron::error::SpannedError),
+    #[display("invalid color: {_0}")]
+    Color(HexColorError),
+}
+
+#[derive(Default)]
+struct BoardThemeLoader;
+
+impl AssetLoader for BoardThemeLoader {
+    type Asset = BoardTheme;
+    type Settings = ();
+    type Error = BoardThemeError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<BoardTheme, BoardThemeError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: BoardThemeFile = ron::de::from_bytes(&bytes)?;
+
+        let color = |hex: &str| Srgba::hex(hex).map(Color::from);
+        let piece_set = file.piece_set.trim_end_matches('/');
+
+        Ok(BoardTheme {
+            light_square: color(&file.light_square)?,
+            dark_square: color(&file.dark_square)?,
+            highlights: HighlightColors {
+                last_move: color(&file.last_move)?,
+                check: color(&file.check)?,
+                move_hint: color(&file.move_hint)?,
+                selection: color(&file.selection)?,
+            },
+            pieces: PieceAssets::load(|name| load_context.load(format!("{piece_set}/{name}"))),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BoardTheme>()
+            .init_asset_loader::<BoardThemeLoader>()
+            .init_resource::<SquareColors>()
+            .add_systems(Startup, load_themes)
+            .add_systems(
+                Update,
+                (
+                    cycle_themes,
+                    apply_theme,
+                    recolor_squares.run_if(resource_changed::<SquareColors>),
+                )
+                    .chain(),
+            )
+            .add_observer(|set: Trigger<SetTheme>, mut commands: Commands| {
+                commands.insert_resource(CurrentTheme(set.0.clone()));
+            });
+    }
+}
+
+fn load_themes(asset_server: Res<AssetServer>, mut commands: Commands) {
+    let themes: Vec<_> = THEMES.iter().map(|path| asset_server.load(*path)).collect();
+    commands.insert_resource(CurrentTheme(themes[0].clone()));
+    commands.insert_resource(LoadedThemes(themes));
+}
+
+fn cycle_themes(
+    keys: Res<ButtonInput<KeyCode>>,
+    themes: Res<LoadedThemes>,
+    current: Res<CurrentTheme>,
+    mut commands: Commands,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.just_pressed(KeyCode::KeyT)
+    {
+        return;
+    }
+
+    let index = themes.0.iter().position(|theme| *theme == current.0);
+    let next = index.map_or(0, |index| (index + 1) % themes.0.len());
+    commands.trigger(SetTheme(themes.0[next].clone()));
+}
+
+// runs every frame, but only does something when the theme was switched or its file (re)loaded
+#[expect(clippy::too_many_arguments)]
+fn apply_theme(
+    current: Res<CurrentTheme>,
+    mut events: EventReader<AssetEvent<BoardTheme>>,
+    themes: Res<Assets<BoardTheme>>,
+    mut piece_assets: ResMut<PieceAssets>,
+    mut square_colors: ResMut<SquareColors>,
+    mut highlight_colors: ResMut<HighlightColors>,
+    mut pieces: Query<(&ColoredPiece, &mut Sprite)>,
+    mut applied: Local<Option<AssetId<BoardTheme>>>,
+) {
+    let reloaded = events.read().fold(false, |reloaded, event| {
+        reloaded
+            || event.is_loaded_with_dependencies(current.id())
+            || event.is_modified(current.id())
+    });
+    if !reloaded && *applied == Some(current.id()) {
+        return;
+    }
+    let Some(theme) = themes.get(&current.0) else {
+        return;
+    };
+    *applied = Some(current.id());
+
+    *piece_assets = theme.pieces.clone();
+    for (piece, mut sprite) in pieces.iter_mut() {
+        sprite.image = piece_assets.get_image(piece.piece, piece.color);
+    }
+    *square_colors = SquareColors {
+        light: theme.light_square,
+        dark: theme.dark_square,
+    };
+    *highlight_colors = theme.highlights.clone();
+}
+
+fn recolor_squares(colors: Res<SquareColors>, mut squares: Query<(&BoardSquare, &mut Sprite)>) {
+    for (square, mut sprite) in squares.iter_mut() {
+        sprite.color = colors.of(square.0);
+    }
+}
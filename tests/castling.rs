@@ -0,0 +1,152 @@
+mod common;
+
+use bevy_game_2::chess_plugin::{
+    Color, ColoredPiece, MoveHistory, MovePlayed, MoveRejected, MoveRequest, Piece, PieceMoved,
+    RejectionReason, Square,
+};
+use common::TestGame;
+
+const BOTH_SIDES: &str = "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1";
+
+fn piece(piece: Piece, color: Color) -> Option<ColoredPiece> {
+    Some(ColoredPiece { piece, color })
+}
+
+#[test]
+fn castles_both_ways() {
+    let mut game = TestGame::from_fen(BOTH_SIDES);
+
+    game.play(&["e1g1", "e8c8"]);
+
+    assert_eq!(game.piece_on(Square::G1), piece(Piece::King, Color::White));
+    assert_eq!(game.piece_on(Square::F1), piece(Piece::Rook, Color::White));
+    assert_eq!(game.piece_on(Square::E1), None);
+    assert_eq!(game.piece_on(Square::H1), None);
+    assert_eq!(game.piece_on(Square::C8), piece(Piece::King, Color::Black));
+    assert_eq!(game.piece_on(Square::D8), piece(Piece::Rook, Color::Black));
+    assert_eq!(game.piece_on(Square::A8), None);
+    game.assert_squares_match_board();
+
+    // the king and the rook both move, in no particular order
+    let mut moved: Vec<_> = game
+        .events::<PieceMoved>()
+        .iter()
+        .map(|moved| (moved.from, moved.to))
+        .collect();
+    moved.sort_by_key(|(from, _)| *from as u8);
+    assert_eq!(
+        moved,
+        [
+            (Square::E1, Square::G1),
+            (Square::H1, Square::F1),
+            (Square::A8, Square::D8),
+            (Square::E8, Square::C8),
+        ]
+    );
+}
+
+#[test]
+fn castling_rights_are_lost_by_moving() {
+    let mut game = TestGame::from_fen(BOTH_SIDES);
+
+    // the king goes and comes back, the rook on a8 too
+    game.play(&["e1f1", "a8b8", "f1e1", "b8a8"]);
+    game.request(MoveRequest::Move {
+        from: Square::E1,
+        to: Square::G1,
+        promotion: None,
+    });
+    game.play(&["a2a3"]);
+    game.request(MoveRequest::Move {
+        from: Square::E8,
+        to: Square::C8,
+        promotion: None,
+    });
+    game.play(&["e8g8"]);
+
+    let rejected: Vec<_> = game
+        .events::<MoveRejected>()
+        .iter()
+        .map(|rejected| rejected.request.to())
+        .collect();
+    assert_eq!(rejected, [Square::G1, Square::C8]);
+    assert_eq!(game.piece_on(Square::G8), piece(Piece::King, Color::Black));
+    assert_eq!(game.piece_on(Square::F8), piece(Piece::Rook, Color::Black));
+}
+
+#[test]
+fn cant_castle_out_of_or_through_check() {
+    // the rook on f3 guards f1, the one on e3 gives check
+    let mut game = TestGame::from_fen("4k3/8/8/8/8/5r2/8/R3K2R w KQ - 0 1");
+    game.request(MoveRequest::Move {
+        from: Square::E1,
+        to: Square::G1,
+        promotion: None,
+    });
+    let mut checked = TestGame::from_fen("4k3/8/8/8/8/4r3/8/R3K2R w KQ - 0 1");
+    checked.request(MoveRequest::Move {
+        from: Square::E1,
+        to: Square::C1,
+        promotion: None,
+    });
+
+    for game in [&mut game, &mut checked] {
+        let rejected = game.events::<MoveRejected>();
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].reason, RejectionReason::IllegalMove);
+        assert_eq!(game.piece_on(Square::E1), piece(Piece::King, Color::White));
+    }
+    // long castling is still fine while only f1 is guarded
+    game.play(&["e1c1"]);
+    assert_eq!(game.piece_on(Square::C1), piece(Piece::King, Color::White));
+    assert_eq!(game.piece_on(Square::D1), piece(Piece::Rook, Color::White));
+}
+
+#[test]
+fn castles_in_chess960() {
+    let mut game = TestGame::from_fen("4k3/8/8/8/8/8/8/R5KR w HA - 0 1");
+
+    // the king stays on g1 and only the rook moves
+    game.request(MoveRequest::Move {
+        from: Square::G1,
+        to: Square::H1,
+        promotion: None,
+    });
+
+    assert_eq!(game.piece_on(Square::G1), piece(Piece::King, Color::White));
+    assert_eq!(game.piece_on(Square::F1), piece(Piece::Rook, Color::White));
+    assert_eq!(game.piece_on(Square::H1), None);
+    game.assert_squares_match_board();
+}
+
+#[test]
+fn castling_is_recorded_one_way() {
+    let king_takes_rook = MoveRequest::Move {
+        from: Square::E1,
+        to: Square::H1,
+        promotion: None,
+    };
+    let two_squares = MoveRequest::Move {
+        from: Square::E1,
+        to: Square::G1,
+        promotion: None,
+    };
+
+    // as engines and the AI ask for it, and as the king is dragged
+    for request in [king_takes_rook, two_squares] {
+        let mut game = TestGame::from_fen(BOTH_SIDES);
+        game.request(request);
+
+        let played: Vec<_> = game
+            .events::<MovePlayed>()
+            .iter()
+            .map(|played| played.mv)
+            .collect();
+        assert_eq!(played, [two_squares]);
+        let history = game.app().world().resource::<MoveHistory>();
+        assert_eq!(
+            history.played().last().map(|played| played.mv),
+            Some(two_squares)
+        );
+    }
+}
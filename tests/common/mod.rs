@@ -0,0 +1,129 @@
+//! A game of chess in a headless `App`, to play scripted moves through and check what came of
+//! them.
+
+// every suite uses its own part of this
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+use bevy_game_2::chess_plugin::{
+    ALL_SQUARES, Board, ChessClock, ChessPlugin, ColoredPiece, GameEnded, GameResult, LoadPosition,
+    MovePlayed, MoveRejected, MoveRequest, PieceCaptured, PieceMoved, PiecePromoted, Square,
+};
+
+/// Every `E` triggered since the game started.
+#[derive(Resource)]
+struct Recorded<E>(Vec<E>);
+
+pub struct TestGame {
+    app: App,
+}
+
+impl TestGame {
+    /// A standard game from the starting position.
+    pub fn new() -> Self {
+        Self::with_plugin(ChessPlugin::default())
+    }
+
+    /// A game hosted by `plugin`, with the 64 squares spawned as the windowed game spawns them.
+    pub fn with_plugin(plugin: ChessPlugin) -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin));
+        record::<MovePlayed>(&mut app);
+        record::<MoveRejected>(&mut app);
+        record::<GameEnded>(&mut app);
+        record::<PieceMoved>(&mut app);
+        record::<PieceCaptured>(&mut app);
+        record::<PiecePromoted>(&mut app);
+
+        for square in ALL_SQUARES {
+            app.world_mut().spawn(square);
+        }
+        app.update();
+
+        Self { app }
+    }
+
+    /// A standard game from `fen`.
+    pub fn from_fen(fen: &str) -> Self {
+        let board = Board::from_fen(fen).unwrap_or_else(|error| panic!("{fen}: {error}"));
+        let mut game = Self::new();
+        game.trigger(LoadPosition(board));
+        game
+    }
+
+    /// Plays `moves`, in UCI, one after the other. Each has to read as a move in the position
+    /// it is played in, but may still be rejected.
+    pub fn play(&mut self, moves: &[&str]) -> &mut Self {
+        for uci in moves {
+            let mv = self
+                .board()
+                .parse_uci(uci)
+                .unwrap_or_else(|_| panic!("{uci} isn't a move in {}", self.board().to_fen()));
+            self.request(mv);
+        }
+        self
+    }
+
+    pub fn request(&mut self, mv: MoveRequest) -> &mut Self {
+        self.trigger(mv)
+    }
+
+    pub fn trigger(&mut self, event: impl Event) -> &mut Self {
+        self.app.world_mut().trigger(event);
+        self.app.update();
+        self
+    }
+
+    pub fn app(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    pub fn board(&self) -> &Board {
+        self.app.world().resource::<Board>()
+    }
+
+    /// The clock, which has to have been inserted.
+    pub fn clock(&self) -> &ChessClock {
+        self.app.world().resource::<ChessClock>()
+    }
+
+    /// How the game ended, if it did.
+    pub fn result(&self) -> Option<GameEnded> {
+        **self.app.world().resource::<GameResult>()
+    }
+
+    /// Every `E` triggered so far, for the events a [`TestGame`] records.
+    pub fn events<E: Event>(&self) -> &[E] {
+        &self.app.world().resource::<Recorded<E>>().0
+    }
+
+    /// The piece on the `square` entity.
+    pub fn piece_on(&mut self, square: Square) -> Option<ColoredPiece> {
+        let world = self.app.world_mut();
+        world
+            .query::<(&Square, Option<&ColoredPiece>)>()
+            .iter(world)
+            .find(|(on, _)| **on == square)
+            .and_then(|(_, piece)| piece.copied())
+    }
+
+    /// Checks that every square entity holds the piece the board has there.
+    pub fn assert_squares_match_board(&mut self) {
+        for square in ALL_SQUARES {
+            let board = self.board();
+            let expected = board
+                .piece_on(square)
+                .zip(board.color_on(square))
+                .map(|(piece, color)| ColoredPiece { piece, color });
+            assert_eq!(self.piece_on(square), expected, "on {square:?}");
+        }
+    }
+}
+
+fn record<E: Event + Clone>(app: &mut App) {
+    app.insert_resource(Recorded::<E>(Vec::new())).add_observer(
+        |event: Trigger<E>, mut recorded: ResMut<Recorded<E>>| {
+            recorded.0.push(event.event().clone());
+        },
+    );
+}
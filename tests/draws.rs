@@ -0,0 +1,111 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::time::TimeUpdateStrategy;
+use bevy_game_2::chess_plugin::{
+    AgreeDraw, ChessClock, Color, GameEndReason, GameEnded, GameOutcome, MoveRejected, Piece,
+    RejectionReason, Square, TimeControl,
+};
+use common::TestGame;
+
+fn drawn_by(reason: GameEndReason) -> Option<GameEnded> {
+    Some(GameEnded {
+        outcome: GameOutcome::Draw,
+        reason,
+    })
+}
+
+#[test]
+fn stalemate() {
+    let mut game = TestGame::from_fen("7k/8/6K1/8/8/8/8/5Q2 w - - 0 1");
+
+    game.play(&["f1f7"]);
+
+    assert_eq!(game.result(), drawn_by(GameEndReason::Stalemate));
+    assert_eq!(game.events::<GameEnded>().len(), 1);
+}
+
+#[test]
+fn insufficient_material() {
+    let mut game = TestGame::from_fen("k7/8/8/8/8/8/1r6/K7 w - - 0 1");
+
+    game.play(&["a1b2"]);
+
+    assert_eq!(game.result(), drawn_by(GameEndReason::InsufficientMaterial));
+}
+
+#[test]
+fn fifty_move_rule() {
+    let mut game = TestGame::from_fen("k7/8/8/8/8/8/8/KR6 w - - 98 60");
+
+    game.play(&["b1b2"]);
+    assert_eq!(game.result(), None);
+    game.play(&["a8a7"]);
+
+    assert_eq!(game.result(), drawn_by(GameEndReason::FiftyMoveRule));
+}
+
+#[test]
+fn threefold_repetition() {
+    let mut game = TestGame::new();
+    let knights_out_and_back = ["g1f3", "g8f6", "f3g1", "f6g8"];
+
+    game.play(&knights_out_and_back);
+    game.play(&knights_out_and_back[..3]);
+    assert_eq!(game.result(), None);
+    // the starting position, for the third time
+    game.play(&knights_out_and_back[3..]);
+
+    assert_eq!(game.result(), drawn_by(GameEndReason::ThreefoldRepetition));
+}
+
+#[test]
+fn agreement() {
+    let mut game = TestGame::new();
+
+    game.play(&["e2e4"]).trigger(AgreeDraw);
+
+    assert_eq!(game.result(), drawn_by(GameEndReason::Agreement));
+}
+
+#[test]
+fn timeout_against_a_bare_king() {
+    let mut game = TestGame::from_fen("k7/8/8/8/8/8/8/KQ6 b - - 0 1");
+    game.app()
+        .insert_resource(ChessClock::new(TimeControl::fischer(
+            Duration::from_secs(1),
+            Duration::ZERO,
+        )))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            200,
+        )));
+
+    // the clock starts with the first move, then white's time runs out
+    game.play(&["a8a7"]);
+    for _ in 0..10 {
+        game.app().update();
+    }
+
+    // black couldn't have checkmated with a lone king
+    assert_eq!(game.result(), drawn_by(GameEndReason::Timeout));
+}
+
+#[test]
+fn no_moves_once_drawn() {
+    let mut game = TestGame::new();
+    game.play(&["e2e4"]).trigger(AgreeDraw);
+    assert_eq!(game.board().side_to_move(), Color::Black);
+
+    // legal, but too late
+    game.play(&["e7e5"]);
+
+    let reasons: Vec<_> = game
+        .events::<MoveRejected>()
+        .iter()
+        .map(|rejected| rejected.reason)
+        .collect();
+    assert_eq!(reasons, [RejectionReason::GameOver]);
+    assert_eq!(game.events::<GameEnded>().len(), 1);
+    assert_eq!(game.board().piece_on(Square::E7), Some(Piece::Pawn));
+}
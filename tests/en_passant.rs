@@ -0,0 +1,59 @@
+mod common;
+
+use bevy_game_2::chess_plugin::{
+    Color, ColoredPiece, MoveRejected, Piece, PieceCaptured, RejectionReason, Square,
+};
+use common::TestGame;
+
+#[test]
+fn takes_the_pawn_that_passed() {
+    let mut game = TestGame::new();
+
+    game.play(&["e2e4", "a7a6", "e4e5", "d7d5", "e5d6"]);
+
+    assert_eq!(
+        game.piece_on(Square::D6),
+        Some(ColoredPiece {
+            piece: Piece::Pawn,
+            color: Color::White
+        })
+    );
+    // the captured pawn wasn't where the capturing one ended up
+    assert_eq!(game.piece_on(Square::D5), None);
+    assert_eq!(
+        game.events::<PieceCaptured>(),
+        [PieceCaptured {
+            square: Square::D5,
+            piece: ColoredPiece {
+                piece: Piece::Pawn,
+                color: Color::Black
+            },
+        }]
+    );
+    game.assert_squares_match_board();
+}
+
+#[test]
+fn only_right_after_the_double_step() {
+    let mut game = TestGame::new();
+
+    game.play(&["e2e4", "a7a6", "e4e5", "d7d5", "a2a3", "a6a5", "e5d6"]);
+
+    let rejected = game.events::<MoveRejected>();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].reason, RejectionReason::IllegalMove);
+    assert_eq!(game.board().piece_on(Square::D5), Some(Piece::Pawn));
+}
+
+#[test]
+fn not_when_it_exposes_the_king() {
+    // both pawns leave the fifth rank, opening it to the rook
+    let mut game = TestGame::from_fen("7k/8/8/K2pP2r/8/8/8/8 w - d6 0 1");
+
+    game.play(&["e5d6"]);
+
+    let rejected = game.events::<MoveRejected>();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].reason, RejectionReason::LeavesKingInCheck);
+    game.assert_squares_match_board();
+}
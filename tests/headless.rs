@@ -0,0 +1,162 @@
+//! The chess plugin on its own, under `MinimalPlugins`: moves go in as `MoveRequest`s, and the
+//! board and the square entities follow.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use bevy::time::TimeUpdateStrategy;
+use bevy_game_2::chess_plugin::{
+    AiPlayer, Board, ChessClock, ChessPlugin, ChessVariant, Color, ColoredPiece, Crazyhouse,
+    KingOfTheHill, MoveHistory, MovePlayed, MoveRejected, Piece, RedoMove, RejectionReason, Square,
+    Standard, TimeControl, UndoMove,
+};
+use common::TestGame;
+
+#[test]
+fn squares_follow_the_board() {
+    let mut game = TestGame::new();
+    let white_pawn = ColoredPiece {
+        piece: Piece::Pawn,
+        color: Color::White,
+    };
+    assert_eq!(game.piece_on(Square::E2), Some(white_pawn));
+    assert_eq!(game.piece_on(Square::E4), None);
+
+    game.play(&["e2e4", "d7d5", "e4d5"]);
+
+    let board = game.board();
+    assert_eq!(board.piece_on(Square::D5), Some(Piece::Pawn));
+    assert_eq!(board.color_on(Square::D5), Some(Color::White));
+    assert_eq!(board.side_to_move(), Color::Black);
+    assert_eq!(game.piece_on(Square::D5), Some(white_pawn));
+    assert_eq!(game.piece_on(Square::E4), None);
+    game.assert_squares_match_board();
+    assert_eq!(game.events::<MovePlayed>().len(), 3);
+}
+
+#[test]
+fn rejected_moves_leave_the_board_alone() {
+    let mut game = TestGame::new();
+
+    game.play(&["e7e5", "e2e5"]);
+
+    let reasons: Vec<_> = game
+        .events::<MoveRejected>()
+        .iter()
+        .map(|rejected| rejected.reason)
+        .collect();
+    assert_eq!(
+        reasons,
+        [
+            RejectionReason::WrongSideToMove,
+            RejectionReason::IllegalMove
+        ]
+    );
+    assert!(*game.board() == Board::default());
+    game.assert_squares_match_board();
+}
+
+#[test]
+fn undo_and_redo_wind_the_clock() {
+    let mut game = TestGame::new();
+    let minute = Duration::from_secs(60);
+    game.app()
+        .insert_resource(ChessClock::new(TimeControl::sudden_death(minute)))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            200,
+        )));
+
+    game.play(&["e2e4", "e7e5"]);
+    let black_when_undone = game.clock().remaining(Color::Black);
+    assert!(game.clock().remaining(Color::White) < minute);
+
+    // white gets back the time spent since black moved
+    game.trigger(UndoMove);
+    assert_eq!(game.clock().remaining(Color::White), minute);
+    assert!(game.clock().remaining(Color::Black) < black_when_undone);
+
+    // and black the time spent since the undo
+    game.trigger(RedoMove);
+    assert_eq!(game.clock().remaining(Color::Black), black_when_undone);
+    assert!(game.clock().remaining(Color::White) < minute);
+}
+
+/// Moves played once a quick [`AiPlayer`] for white has had the time to move.
+fn moves_by_ai(variant: Arc<dyn ChessVariant>) -> usize {
+    let mut game = TestGame::with_plugin(ChessPlugin { variant });
+    game.app().world_mut().spawn(AiPlayer {
+        color: Color::White,
+        max_depth: 1,
+        time_limit: None,
+    });
+
+    for _ in 0..200 {
+        game.app().update();
+        if !game.events::<MovePlayed>().is_empty() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    game.events::<MovePlayed>().len()
+}
+
+/// A variant of its own that happens to call itself standard.
+struct CalledStandard;
+
+impl ChessVariant for CalledStandard {
+    fn name(&self) -> &'static str {
+        "Standard"
+    }
+}
+
+#[test]
+fn ai_sits_out_variants_it_cant_play() {
+    assert_eq!(moves_by_ai(Arc::new(Crazyhouse)), 0);
+    assert_eq!(moves_by_ai(Arc::new(KingOfTheHill)), 0);
+    assert_eq!(moves_by_ai(Arc::new(CalledStandard)), 0);
+    // while it does move in standard chess
+    assert_eq!(moves_by_ai(Arc::new(Standard)), 1);
+}
+
+#[test]
+fn crazyhouse_drops_through_the_plugin() {
+    let mut game = TestGame::with_plugin(ChessPlugin {
+        variant: Arc::new(Crazyhouse),
+    });
+
+    // white drops the taken pawn with all 16 pieces still on the board
+    game.play(&["e2e4", "d7d5", "e4d5", "g8f6", "P@e4"]);
+
+    assert_eq!(game.events::<MovePlayed>().len(), 5);
+    assert!(game.events::<MoveRejected>().is_empty());
+    assert_eq!(
+        game.piece_on(Square::E4).map(|piece| piece.piece),
+        Some(Piece::Pawn)
+    );
+    game.assert_squares_match_board();
+}
+
+#[test]
+fn counts_only_the_captures_played() {
+    // black is missing a queen and a rook from the start, which nobody took in this game
+    let mut game = TestGame::from_fen("1nb1kbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQk - 0 1");
+    game.play(&["d7d5", "e4d5", "g8f6", "f1b5", "c7c6", "d5c6", "b8c6"]);
+
+    let captured =
+        |game: &mut TestGame, color| game.app().world().resource::<MoveHistory>().captured(color);
+    assert_eq!(
+        captured(&mut game, Color::Black),
+        [
+            (Piece::Pawn, 2),
+            (Piece::Knight, 0),
+            (Piece::Bishop, 0),
+            (Piece::Rook, 0),
+            (Piece::Queen, 0),
+        ]
+    );
+    assert_eq!(captured(&mut game, Color::White)[0], (Piece::Pawn, 1));
+
+    game.trigger(UndoMove);
+    assert_eq!(captured(&mut game, Color::White)[0], (Piece::Pawn, 0));
+}
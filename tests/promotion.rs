@@ -0,0 +1,109 @@
+mod common;
+
+use bevy_game_2::chess_plugin::{
+    Color, ColoredPiece, MoveRejected, MoveRequest, Piece, PieceCaptured, PieceMoved,
+    PiecePromoted, RejectionReason, Square,
+};
+use common::TestGame;
+
+const PROMOTING: &str = "3r3k/4P3/8/8/8/8/8/4K3 w - - 0 1";
+
+fn mv(from: Square, to: Square, promotion: Option<Piece>) -> MoveRequest {
+    MoveRequest::Move {
+        from,
+        to,
+        promotion,
+    }
+}
+
+fn white(piece: Piece) -> Option<ColoredPiece> {
+    Some(ColoredPiece {
+        piece,
+        color: Color::White,
+    })
+}
+
+#[test]
+fn needs_a_piece_to_promote_to() {
+    let mut game = TestGame::from_fen(PROMOTING);
+    let push = mv(Square::E7, Square::E8, None);
+
+    assert!(game.board().needs_promotion(push));
+    assert!(
+        game.board()
+            .needs_promotion(mv(Square::E7, Square::D8, None))
+    );
+    assert!(
+        !game
+            .board()
+            .needs_promotion(mv(Square::E7, Square::E8, Some(Piece::Queen)))
+    );
+    assert!(
+        !game
+            .board()
+            .needs_promotion(mv(Square::E1, Square::E2, None))
+    );
+
+    game.request(push);
+
+    let rejected = game.events::<MoveRejected>();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].reason, RejectionReason::MissingPromotion);
+    assert_eq!(game.piece_on(Square::E7), white(Piece::Pawn));
+}
+
+#[test]
+fn promotes_to_the_piece_asked_for() {
+    for (uci, piece) in [
+        ("e7e8q", Piece::Queen),
+        ("e7e8r", Piece::Rook),
+        ("e7e8b", Piece::Bishop),
+        ("e7e8n", Piece::Knight),
+    ] {
+        let mut game = TestGame::from_fen(PROMOTING);
+
+        game.play(&[uci]);
+
+        assert_eq!(game.piece_on(Square::E8), white(piece));
+        assert_eq!(game.piece_on(Square::E7), None);
+        assert_eq!(
+            game.events::<PiecePromoted>(),
+            [PiecePromoted {
+                square: Square::E8,
+                piece: white(piece).unwrap(),
+            }]
+        );
+        // the pawn is what moved there
+        assert_eq!(
+            game.events::<PieceMoved>()[0].piece,
+            white(Piece::Pawn).unwrap()
+        );
+    }
+}
+
+#[test]
+fn promotes_while_capturing() {
+    let mut game = TestGame::from_fen(PROMOTING);
+
+    game.request(mv(Square::E7, Square::D8, Some(Piece::Knight)));
+
+    assert_eq!(game.piece_on(Square::D8), white(Piece::Knight));
+    assert_eq!(
+        game.events::<PieceCaptured>()[0].piece,
+        ColoredPiece {
+            piece: Piece::Rook,
+            color: Color::Black
+        }
+    );
+    game.assert_squares_match_board();
+}
+
+#[test]
+fn only_pawns_on_the_last_rank_promote() {
+    let mut game = TestGame::from_fen("7k/8/4P3/8/8/8/8/4K3 w - - 0 1");
+
+    game.request(mv(Square::E6, Square::E7, Some(Piece::Queen)));
+
+    assert_eq!(game.events::<MoveRejected>().len(), 1);
+    assert_eq!(game.piece_on(Square::E6), white(Piece::Pawn));
+}